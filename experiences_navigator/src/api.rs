@@ -1,9 +1,11 @@
 use {
-    experiences_types_lib::types::ExperiencesHostname,
-    leptos::use_context,
+    experiences_types_lib::types::{ExperienceChange, ExperiencesHostname},
+    leptos::{create_signal, on_cleanup, provide_context, use_context, ReadSignal},
     reqwest::Client,
     timeline_types::api::APIResult,
     url::{ParseError, Url},
+    wasm_bindgen::{closure::Closure, JsCast},
+    web_sys::{EventSource, EventSourceInit, MessageEvent},
 };
 
 pub async fn api_request<T, V>(endpoint: &str, request: &V) -> APIResult<T>
@@ -29,3 +31,50 @@ pub fn relative_url(path: &str) -> Result<Url, ParseError> {
     let experiences_host: ExperiencesHostname = use_context().unwrap();
     Url::parse(&experiences_host.0)?.join(path)
 }
+
+#[derive(Clone, Copy)]
+pub struct Changes(pub ReadSignal<Option<ExperienceChange>>);
+
+pub fn provide_changes() -> ReadSignal<Option<ExperienceChange>> {
+    let (changes, write_changes) = create_signal(None);
+    provide_context(Changes(changes));
+
+    let mut init = EventSourceInit::new();
+    init.with_credentials(true);
+    let source = match EventSource::new_with_event_source_init_dict(
+        relative_url("/api/events").unwrap().as_str(),
+        &init,
+    ) {
+        Ok(v) => v,
+        Err(_) => return changes,
+    };
+
+    let listener = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+        if let Some(data) = event.data().as_string() {
+            if let Ok(change) = serde_json::from_str(&data) {
+                write_changes(Some(change))
+            }
+        }
+    });
+    for event in [
+        "experience_changed",
+        "cover_regenerated",
+        "navigator_position_changed",
+    ] {
+        let _ = source.add_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+    }
+
+    on_cleanup(move || {
+        source.close();
+        drop(listener);
+    });
+
+    changes
+}
+
+pub fn use_changes() -> ReadSignal<Option<ExperienceChange>> {
+    match use_context::<Changes>() {
+        Some(v) => v.0,
+        None => provide_changes(),
+    }
+}
@@ -1,13 +1,18 @@
 use {
     crate::{
-        api::{api_request, relative_url},
+        api::{api_request, relative_url, use_changes},
         wrappers::Band,
     },
     ::core::f64,
-    experiences_types_lib::types::ExperienceConnectionResponse,
+    experiences_types_lib::types::{
+        AccessEntry, AccessRole, AccessSubject, ConnectionKind, ConnectionRelationship,
+        ConnectionDetails, CreateShareRequest, ExperienceChange, ExperienceConnection,
+        ExperienceConnectionResponse, ExperienceDetails, ExperienceStats, ShareToken,
+    },
     leptos::*,
     leptos_use::*,
     stylers::style,
+    timeline_types::timing::Timing,
 };
 
 #[component]
@@ -154,6 +159,45 @@ pub fn Navigator(
             z-index: 1;
         }
 
+        .childConnection {
+            border-style: dashed;
+            border-color: var(--accentColor1);
+        }
+
+        .parentConnection {
+            border-color: var(--accentColor2);
+        }
+
+        .childExperience {
+            transform: translate(-50%, -50%) scale(0.8);
+        }
+
+        .sequenceConnection {
+            border-color: var(--accentColor1Light);
+        }
+
+        .samePeopleConnection {
+            border-style: dotted;
+        }
+
+        .samePlaceConnection {
+            border-style: double;
+            border-width: 4px;
+        }
+
+        .experienceTime {
+            color: var(--darkColor);
+            font-size: 70%;
+            text-align: center;
+        }
+
+        .relationshipFilter {
+            position: absolute;
+            top: var(--contentSpacing);
+            left: var(--contentSpacing);
+            z-index: 3;
+        }
+
         .connectedExperiencesWrapper {
             position: absolute;
             top: 50%;
@@ -177,6 +221,8 @@ pub fn Navigator(
 
     let expanded = create_memo(move |_| expanded());
 
+    let (connection_filter, write_connection_filter) = create_signal(None::<ConnectionFilter>);
+
     let (read_width, write_width) = create_signal(0.0);
     let (read_height, write_height) = create_signal(0.0);
 
@@ -185,8 +231,74 @@ pub fn Navigator(
             .await
     });
 
+    let changes = use_changes();
+    let (cover_version, write_cover_version) = create_signal(0);
+    provide_context(CoverVersion(cover_version));
+    let concerns = move |id: &str| {
+        id == experience.get_untracked()
+            || connections.with_untracked(|v| {
+                matches!(v, Some(Ok(v)) if v.connections.iter().any(|v| v.id == id))
+            })
+    };
+    create_effect(move |_| match changes() {
+        Some(ExperienceChange::CoverRegenerated { experience_id }) => {
+            if concerns(&experience_id) {
+                write_cover_version.update(|v| *v += 1)
+            }
+        }
+        Some(ExperienceChange::NavigatorPositionChanged { .. }) | None => {}
+        Some(
+            ExperienceChange::Connected {
+                experience_id,
+                connected_id,
+            }
+            | ExperienceChange::Disconnected {
+                experience_id,
+                connected_id,
+            },
+        ) => {
+            if concerns(&experience_id) || concerns(&connected_id) {
+                connections.refetch()
+            }
+        }
+        Some(change) => {
+            if concerns(change.experience_id()) {
+                connections.refetch()
+            }
+        }
+    });
+
     view! { class=style,
         <div class="navigator_wrapper" class:collapsed=move || !expanded()>
+            <select
+                class="relationshipFilter"
+                style:display=move || if expanded() { "block" } else { "none" }
+                on:change=move |e| {
+                    write_connection_filter(
+                        match event_target_value(&e).as_str() {
+                            "parent" => Some(ConnectionFilter::Kind(ConnectionKind::Parent)),
+                            "child" => Some(ConnectionFilter::Kind(ConnectionKind::Child)),
+                            v => {
+                                v.parse::<usize>()
+                                    .ok()
+                                    .and_then(|v| ConnectionRelationship::ALL.get(v).cloned())
+                                    .map(ConnectionFilter::Relationship)
+                            }
+                        },
+                    )
+                }
+            >
+                <option value="all">All connections</option>
+                {ConnectionRelationship::ALL
+                    .iter()
+                    .enumerate()
+                    .map(|(index, relationship)| {
+                        view! { <option value=index.to_string()>{relationship.name()}</option> }
+                    })
+                    .collect_view()}
+                <option value="parent">Parent</option>
+                <option value="child">Children</option>
+            </select>
             <Suspense fallback=move || {
                 view! { <Info>Loading</Info> }
             }>
@@ -205,12 +317,20 @@ pub fn Navigator(
                                                 write_height.set(rect.height());
                                             },
                                         );
-                                        let view = connections
+                                        let visible_connections = connections
                                             .connections
+                                            .iter()
+                                            .filter(|connection| {
+                                                connection_filter()
+                                                    .map(|v| v.matches(connection))
+                                                    .unwrap_or(true)
+                                            })
+                                            .collect::<Vec<_>>();
+                                        let view = visible_connections
                                             .iter()
                                             .enumerate()
                                             .map(|(pos, connection)| {
-                                                let deg = pos as f64 / connections.connections.len() as f64
+                                                let deg = pos as f64 / visible_connections.len() as f64
                                                     * 360.0 + 270.0 + 0.0;
                                                 let rad = deg * f64::consts::PI / 180.0;
                                                 let x = move || {
@@ -242,10 +362,38 @@ pub fn Navigator(
                                                     res - 90.
                                                 };
                                                 let connection_id = connection.id.clone();
+                                                let is_child = connection.kind
+                                                    == ConnectionKind::Child;
+                                                let is_parent = connection.kind
+                                                    == ConnectionKind::Parent;
+                                                let relationship = connection
+                                                    .details
+                                                    .relationship
+                                                    .clone();
+                                                let is_sequence = relationship
+                                                    == ConnectionRelationship::FollowedBy
+                                                    || relationship
+                                                        == ConnectionRelationship::PrecededBy;
+                                                let is_same_people = relationship
+                                                    == ConnectionRelationship::SamePeople;
+                                                let is_same_place = relationship
+                                                    == ConnectionRelationship::SamePlace;
+                                                let label = match connection.kind {
+                                                    ConnectionKind::Parent => "Parent".to_string(),
+                                                    ConnectionKind::Child => "Child".to_string(),
+                                                    ConnectionKind::Peer => {
+                                                        connection
+                                                            .details
+                                                            .label
+                                                            .clone()
+                                                            .unwrap_or(relationship.name().to_string())
+                                                    }
+                                                };
 
                                                 view! { class=style,
                                                     <div
                                                         class="experience_wrap"
+                                                        class:childExperience=is_child
                                                         style:left=move || format!("{}px", x())
                                                         style:top=move || format!("{}px", y())
                                                     >
@@ -260,6 +408,12 @@ pub fn Navigator(
                                                     </div>
                                                     <div
                                                         class="connection"
+                                                        class:childConnection=is_child
+                                                        class:parentConnection=is_parent
+                                                        class:sequenceConnection=is_sequence
+                                                        class:samePeopleConnection=is_same_people
+                                                        class:samePlaceConnection=is_same_place
+                                                        title=label
                                                         style:width=move || format!("{}px", connection_len())
                                                         style:transform=move || {
                                                             format!("rotate({}deg)", connection_rotation())
@@ -282,6 +436,16 @@ pub fn Navigator(
                                                     id=experience()
                                                     enlarge=true
                                                 />
+                                                {connections
+                                                    .experience_time
+                                                    .as_ref()
+                                                    .map(|time| {
+                                                        view! { class=style,
+                                                            <div class="experienceTime">
+                                                                {format_timing(time)}
+                                                            </div>
+                                                        }
+                                                    })}
                                             </div>
                                         }
                                             .into_view()
@@ -337,40 +501,54 @@ pub fn Navigator(
                                         <div style:display=move || {
                                             if expanded() { "block" } else { "none" }
                                         }>
-                                            <Band
-                                                color="var(--accentColor1)"
-                                                click=Callback::new(move |_| {
-                                                    let experience_id = experience();
-                                                    let new_connection_status = !connections().public;
-                                                    write_connections
-                                                        .update(|v| v.public = new_connection_status);
-                                                    spawn_local(async move {
-                                                        if let Err(e) = api_request::<
-                                                            (),
-                                                            _,
-                                                        >(
-                                                                &format!("/experience/{}/visibility", experience_id),
-                                                                &new_connection_status,
-                                                            )
-                                                            .await
-                                                        {
-                                                            window()
-                                                                .alert_with_message(
-                                                                    &format!("Unable to change visibility: {}", e),
+                                            <Show when=move || connections().owner>
+                                                <Band
+                                                    color="var(--accentColor1)"
+                                                    click=Callback::new(move |_| {
+                                                        let experience_id = experience();
+                                                        let new_connection_status = !connections().public;
+                                                        write_connections
+                                                            .update(|v| v.public = new_connection_status);
+                                                        spawn_local(async move {
+                                                            if let Err(e) = api_request::<
+                                                                (),
+                                                                _,
+                                                            >(
+                                                                    &format!("/experience/{}/visibility", experience_id),
+                                                                    &new_connection_status,
                                                                 )
-                                                                .unwrap();
+                                                                .await
+                                                            {
+                                                                window()
+                                                                    .alert_with_message(
+                                                                        &format!("Unable to change visibility: {}", e),
+                                                                    )
+                                                                    .unwrap();
+                                                            }
+                                                        })
+                                                    })
+                                                >
+                                                    <img src=move || {
+                                                        if connections().public {
+                                                            relative_url("/icons/public.svg").unwrap().to_string()
+                                                        } else {
+                                                            relative_url("/icons/private.svg").unwrap().to_string()
                                                         }
+                                                    } />
+                                                </Band>
+                                                <ShareLinks experience />
+                                                <AccessList experience />
+                                            </Show>
+                                            <Show when=move || connections().editable>
+                                                <ExperienceDetailsEditor experience />
+                                                <ConnectionEditor
+                                                    experience
+                                                    connections=Signal::derive(move || {
+                                                        connections().connections
                                                     })
-                                                })
-                                            >
-                                                <img src=move || {
-                                                    if connections().public {
-                                                        relative_url("/icons/public.svg").unwrap().to_string()
-                                                    } else {
-                                                        relative_url("/icons/private.svg").unwrap().to_string()
-                                                    }
-                                                } />
-                                            </Band>
+                                                />
+                                            </Show>
+                                            <ExperienceStatsView experience />
                                         </div>
                                     }
                                         .into_view()
@@ -393,6 +571,638 @@ pub fn Navigator(
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+enum ConnectionFilter {
+    Relationship(ConnectionRelationship),
+    Kind(ConnectionKind),
+}
+
+impl ConnectionFilter {
+    fn matches(&self, connection: &ExperienceConnection) -> bool {
+        match self {
+            ConnectionFilter::Relationship(v) => {
+                connection.kind == ConnectionKind::Peer && connection.details.relationship == *v
+            }
+            ConnectionFilter::Kind(v) => connection.kind == *v,
+        }
+    }
+}
+
+#[component]
+pub fn ConnectionEditor(
+    #[prop(into)] experience: Signal<String>,
+    #[prop(into)] connections: Signal<Vec<ExperienceConnection>>,
+) -> impl IntoView {
+    let style = style! {
+        .connections {
+            background-color: var(--accentColor3Light);
+            color: var(--lightColor);
+            padding: var(--contentSpacing);
+            display: flex;
+            flex-direction: column;
+            gap: var(--contentSpacing);
+        }
+
+        .connection {
+            display: flex;
+            flex-direction: row;
+            align-items: center;
+            gap: var(--contentSpacing);
+        }
+    };
+
+    let save = move |connected_id: String, details: ConnectionDetails| {
+        let experience = experience.get_untracked();
+        spawn_local(async move {
+            if let Err(e) = api_request::<(), _>(
+                &format!("/experience/{}/connection/{}", experience, connected_id),
+                &details,
+            )
+            .await
+            {
+                window()
+                    .alert_with_message(&format!("Unable to change connection: {}", e))
+                    .unwrap();
+            }
+        })
+    };
+
+    view! { class=style,
+        <div class="connections">
+            {move || {
+                connections()
+                    .into_iter()
+                    .filter(|v| v.kind == ConnectionKind::Peer)
+                    .map(|connection| {
+                        let (relationship, write_relationship) = create_signal(
+                            connection.details.relationship.clone(),
+                        );
+                        let (label, write_label) = create_signal(
+                            connection.details.label.clone().unwrap_or_default(),
+                        );
+                        let id = connection.id.clone();
+                        view! { class=style,
+                            <div class="connection">
+                                <div>{connection.name.clone()}</div>
+                                <select on:change=move |e| {
+                                    if let Some(v) = event_target_value(&e)
+                                        .parse::<usize>()
+                                        .ok()
+                                        .and_then(|v| ConnectionRelationship::ALL.get(v).cloned())
+                                    {
+                                        write_relationship(v)
+                                    }
+                                }>
+                                    {ConnectionRelationship::ALL
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(index, v)| {
+                                            view! {
+                                                <option
+                                                    value=index.to_string()
+                                                    selected=*v == connection.details.relationship
+                                                >
+                                                    {v.name()}
+                                                </option>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </select>
+                                <input
+                                    type="text"
+                                    placeholder="Label"
+                                    prop:value=label
+                                    on:input=move |e| write_label(event_target_value(&e))
+                                />
+                                <button on:click=move |_| {
+                                    let label = label.get_untracked();
+                                    save(
+                                        id.clone(),
+                                        ConnectionDetails {
+                                            relationship: relationship.get_untracked(),
+                                            label: if label.is_empty() { None } else { Some(label) },
+                                        },
+                                    )
+                                }>Save</button>
+                            </div>
+                        }
+                    })
+                    .collect_view()
+            }}
+        </div>
+    }
+}
+
+#[component]
+pub fn ExperienceStatsView(#[prop(into)] experience: Signal<String>) -> impl IntoView {
+    let style = style! {
+        .stats {
+            background-color: var(--accentColor3Light);
+            padding: var(--contentSpacing);
+            display: flex;
+            flex-direction: column;
+            gap: var(--contentSpacing);
+        }
+
+        .histogram {
+            display: flex;
+            flex-direction: row;
+            align-items: flex-end;
+            gap: 2px;
+            height: 50px;
+        }
+
+        .bar {
+            flex: 1;
+            background-color: var(--accentColor3);
+        }
+    };
+
+    let stats = create_resource(experience, |experience| async move {
+        api_request::<ExperienceStats, _>(&format!("/experience/{}/stats", experience), &()).await
+    });
+
+    let changes = use_changes();
+    create_effect(move |_| {
+        if let Some(change) = changes() {
+            if change.experience_id() == experience.get_untracked() {
+                stats.refetch();
+            }
+        }
+    });
+
+    view! {
+        <Suspense fallback=move || {
+            view! { <Info>Loading</Info> }
+        }>
+            {move || {
+                stats()
+                    .map(|stats| match stats {
+                        Ok(stats) => {
+                            let max_per_day = stats
+                                .events_per_day
+                                .values()
+                                .max()
+                                .cloned()
+                                .unwrap_or(1);
+                            let mut plugins = stats.events.into_iter().collect::<Vec<_>>();
+                            plugins.sort_by(|a, b| b.1.cmp(&a.1));
+                            view! { class=style,
+                                <div class="stats">
+                                    <div>
+                                        {format!(
+                                            "{} events, {} favorites, {} connections, {:.1} KB on disk",
+                                            plugins.iter().map(|v| v.1).sum::<usize>(),
+                                            stats.favorites,
+                                            stats.connections,
+                                            stats.size as f64 / 1024.0,
+                                        )}
+                                    </div>
+                                    {stats.time.as_ref().map(|time| view! { <div>{format_timing(time)}</div> })}
+                                    <div>
+                                        {plugins
+                                            .into_iter()
+                                            .map(|(plugin, count)| {
+                                                view! { <div>{format!("{:?}: {}", plugin, count)}</div> }
+                                            })
+                                            .collect_view()}
+                                    </div>
+                                    <div class="histogram">
+                                        {stats
+                                            .events_per_day
+                                            .into_iter()
+                                            .map(|(day, count)| {
+                                                view! { class=style,
+                                                    <div
+                                                        class="bar"
+                                                        title=format!("{}: {}", day, count)
+                                                        style:height=format!(
+                                                            "{}%",
+                                                            count as f64 / max_per_day as f64 * 100.0,
+                                                        )
+                                                    ></div>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </div>
+                                </div>
+                            }
+                                .into_view()
+                        }
+                        Err(e) => {
+                            view! { <Error>Error loading statistics: {e.to_string()}</Error> }
+                                .into_view()
+                        }
+                    })
+            }}
+        </Suspense>
+    }
+}
+
+#[component]
+pub fn ShareLinks(#[prop(into)] experience: Signal<String>) -> impl IntoView {
+    let style = style! {
+        .shares {
+            background-color: var(--accentColor3Light);
+            color: var(--lightColor);
+            padding: var(--contentSpacing);
+            display: flex;
+            flex-direction: column;
+            gap: var(--contentSpacing);
+        }
+
+        .share {
+            display: flex;
+            flex-direction: row;
+            align-items: center;
+            gap: var(--contentSpacing);
+            word-break: break-all;
+        }
+
+        .limits {
+            display: flex;
+            flex-direction: row;
+            gap: var(--contentSpacing);
+        }
+    };
+
+    let (expires, write_expires) = create_signal(String::new());
+    let (max_views, write_max_views) = create_signal(String::new());
+
+    let shares = create_resource(experience, |experience| async move {
+        api_request::<Vec<ShareToken>, _>(&format!("/experience/{}/shares", experience), &())
+            .await
+    });
+
+    let create_share = move |_| {
+        let experience = experience.get_untracked();
+        let request = CreateShareRequest {
+            expires_in_hours: expires.get_untracked().parse().ok(),
+            max_views: max_views.get_untracked().parse().ok(),
+        };
+        spawn_local(async move {
+            match api_request::<ShareToken, _>(
+                &format!("/experience/{}/shares/create", experience),
+                &request,
+            )
+            .await
+            {
+                Ok(_) => shares.refetch(),
+                Err(e) => window()
+                    .alert_with_message(&format!("Unable to create share link: {}", e))
+                    .unwrap(),
+            }
+        })
+    };
+
+    let revoke_share = move |token: String| {
+        let experience = experience.get_untracked();
+        spawn_local(async move {
+            match api_request::<(), _>(
+                &format!("/experience/{}/shares/{}/revoke", experience, token),
+                &(),
+            )
+            .await
+            {
+                Ok(_) => shares.refetch(),
+                Err(e) => window()
+                    .alert_with_message(&format!("Unable to revoke share link: {}", e))
+                    .unwrap(),
+            }
+        })
+    };
+
+    view! { class=style,
+        <div class="shares">
+            <div class="limits">
+                <input
+                    type="number"
+                    min="1"
+                    placeholder="Expires in hours"
+                    on:change=move |e| write_expires(event_target_value(&e))
+                />
+                <input
+                    type="number"
+                    min="1"
+                    placeholder="Maximum views"
+                    on:change=move |e| write_max_views(event_target_value(&e))
+                />
+            </div>
+            <Band click=Callback::new(create_share)>Create share link</Band>
+            <Suspense fallback=move || {
+                view! { <Info>Loading</Info> }
+            }>
+                {move || {
+                    shares()
+                        .map(|shares| match shares {
+                            Ok(shares) => {
+                                shares
+                                    .into_iter()
+                                    .map(|share| {
+                                        let token = share.token.clone();
+                                        view! { class=style,
+                                            <div class="share">
+                                                <a href=relative_url(
+                                                        &format!("/api/share/{}", share.token),
+                                                    )
+                                                    .unwrap()
+                                                    .to_string()>{share.token.clone()}</a>
+                                                <div>
+                                                    {format!(
+                                                        "{} views{}{}",
+                                                        share.views,
+                                                        share
+                                                            .max_views
+                                                            .map(|v| format!(" of {}", v))
+                                                            .unwrap_or_default(),
+                                                        share
+                                                            .expires
+                                                            .map(|v| format!(", expires {}", v.format("%Y-%m-%d %H:%M")))
+                                                            .unwrap_or_default(),
+                                                    )}
+                                                </div>
+                                                <button on:click=move |_| revoke_share(
+                                                    token.clone(),
+                                                )>Revoke</button>
+                                            </div>
+                                        }
+                                    })
+                                    .collect_view()
+                            }
+                            Err(e) => {
+                                view! { <Error>Error loading share links: {e.to_string()}</Error> }
+                                    .into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+pub fn AccessList(#[prop(into)] experience: Signal<String>) -> impl IntoView {
+    let style = style! {
+        .access {
+            background-color: var(--accentColor3Light);
+            color: var(--lightColor);
+            padding: var(--contentSpacing);
+            display: flex;
+            flex-direction: column;
+            gap: var(--contentSpacing);
+        }
+
+        .entry {
+            display: flex;
+            flex-direction: row;
+            align-items: center;
+            gap: var(--contentSpacing);
+        }
+    };
+
+    let acl = create_rw_signal(Vec::<AccessEntry>::new());
+    let (name, write_name) = create_signal(String::new());
+    let (group, write_group) = create_signal(false);
+    let (editor, write_editor) = create_signal(false);
+
+    let loaded = create_resource(experience, move |experience| async move {
+        let res =
+            api_request::<Vec<AccessEntry>, _>(&format!("/experience/{}/acl", experience), &())
+                .await;
+        if let Ok(v) = &res {
+            acl.set(v.clone());
+        }
+        res.map(|_| ())
+    });
+
+    let save = move || {
+        let experience = experience.get_untracked();
+        let request = acl.get_untracked();
+        spawn_local(async move {
+            if let Err(e) =
+                api_request::<(), _>(&format!("/experience/{}/acl/set", experience), &request)
+                    .await
+            {
+                window()
+                    .alert_with_message(&format!("Unable to change access list: {}", e))
+                    .unwrap();
+            }
+        })
+    };
+
+    let add = move |_| {
+        let name = name.get_untracked();
+        if name.is_empty() {
+            return;
+        }
+        let entry = AccessEntry {
+            subject: if group.get_untracked() {
+                AccessSubject::Group(name)
+            } else {
+                AccessSubject::User(name)
+            },
+            role: if editor.get_untracked() {
+                AccessRole::Editor
+            } else {
+                AccessRole::Viewer
+            },
+        };
+        acl.update(|v| {
+            v.retain(|v| v.subject != entry.subject);
+            v.push(entry);
+        });
+        save();
+    };
+
+    let remove = move |index: usize| {
+        acl.update(|v| {
+            v.remove(index);
+        });
+        save();
+    };
+
+    view! { class=style,
+        <div class="access">
+            <Suspense fallback=move || {
+                view! { <Info>Loading</Info> }
+            }>
+                {move || {
+                    loaded()
+                        .map(|loaded| match loaded {
+                            Ok(_) => {
+                                view! { class=style,
+                                    {move || {
+                                        acl()
+                                            .into_iter()
+                                            .enumerate()
+                                            .map(|(index, entry)| {
+                                                let subject = match entry.subject {
+                                                    AccessSubject::User(v) => v,
+                                                    AccessSubject::Group(v) => format!("Group {}", v),
+                                                };
+                                                view! { class=style,
+                                                    <div class="entry">
+                                                        <div>{format!("{}: {:?}", subject, entry.role)}</div>
+                                                        <button on:click=move |_| remove(index)>Remove</button>
+                                                    </div>
+                                                }
+                                            })
+                                            .collect_view()
+                                    }}
+                                    <div class="entry">
+                                        <input
+                                            type="text"
+                                            placeholder="User or group"
+                                            on:change=move |e| write_name(event_target_value(&e))
+                                        />
+                                        <label>
+                                            <input
+                                                type="checkbox"
+                                                on:change=move |e| write_group(event_target_checked(&e))
+                                            />
+                                            Group
+                                        </label>
+                                        <label>
+                                            <input
+                                                type="checkbox"
+                                                on:change=move |e| write_editor(event_target_checked(&e))
+                                            />
+                                            Editor
+                                        </label>
+                                    </div>
+                                    <Band click=Callback::new(add)>Share with</Band>
+                                }
+                                    .into_view()
+                            }
+                            Err(e) => {
+                                view! { <Error>Error loading access list: {e.to_string()}</Error> }
+                                    .into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+pub fn ExperienceDetailsEditor(#[prop(into)] experience: Signal<String>) -> impl IntoView {
+    let style = style! {
+        .details {
+            background-color: var(--accentColor3Light);
+            color: var(--lightColor);
+            padding: var(--contentSpacing);
+            display: flex;
+            flex-direction: column;
+            gap: var(--contentSpacing);
+        }
+    };
+
+    let (tags, write_tags) = create_signal(String::new());
+    let (metadata, write_metadata) = create_signal(String::new());
+
+    let loaded = create_resource(experience, move |experience| async move {
+        let res = api_request::<ExperienceDetails, _>(
+            &format!("/experience/{}/details", experience),
+            &(),
+        )
+        .await;
+        if let Ok(v) = &res {
+            write_tags(v.tags.join(", "));
+            let mut metadata = v
+                .metadata
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, value))
+                .collect::<Vec<_>>();
+            metadata.sort();
+            write_metadata(metadata.join("\n"));
+        }
+        res.map(|_| ())
+    });
+
+    let save = move |_| {
+        let experience = experience.get_untracked();
+        let request = ExperienceDetails {
+            tags: tags
+                .get_untracked()
+                .split(',')
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect(),
+            metadata: metadata
+                .get_untracked()
+                .lines()
+                .filter_map(|v| v.split_once(':'))
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .filter(|(key, _)| !key.is_empty())
+                .collect(),
+        };
+        spawn_local(async move {
+            if let Err(e) =
+                api_request::<(), _>(&format!("/experience/{}/details/set", experience), &request)
+                    .await
+            {
+                window()
+                    .alert_with_message(&format!("Unable to save tags and metadata: {}", e))
+                    .unwrap();
+            }
+        })
+    };
+
+    view! { class=style,
+        <div class="details">
+            <Suspense fallback=move || {
+                view! { <Info>Loading</Info> }
+            }>
+                {move || {
+                    loaded()
+                        .map(|loaded| match loaded {
+                            Ok(_) => {
+                                view! {
+                                    <input
+                                        type="text"
+                                        placeholder="Tags, separated by commas"
+                                        prop:value=tags
+                                        on:input=move |e| write_tags(event_target_value(&e))
+                                    />
+                                    <textarea
+                                        placeholder="One key: value per line"
+                                        prop:value=metadata
+                                        on:input=move |e| write_metadata(event_target_value(&e))
+                                    ></textarea>
+                                    <Band click=Callback::new(save)>Save tags and metadata</Band>
+                                }
+                                    .into_view()
+                            }
+                            Err(e) => {
+                                view! { <Error>Error loading tags and metadata: {e.to_string()}</Error> }
+                                    .into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+fn format_timing(timing: &Timing) -> String {
+    match timing {
+        Timing::Instant(v) => v.format("%Y-%m-%d").to_string(),
+        Timing::Range(v) => {
+            let start = v.start.format("%Y-%m-%d").to_string();
+            let end = v.end.format("%Y-%m-%d").to_string();
+            if start == end {
+                start
+            } else {
+                format!("{} - {}", start, end)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct CoverVersion(ReadSignal<usize>);
+
 #[component]
 pub fn ExperienceCard(
     #[prop(into)] name: MaybeSignal<String>,
@@ -429,10 +1239,20 @@ pub fn ExperienceCard(
         }
     };
 
+    let cover_version = use_context::<CoverVersion>().map(|v| v.0);
+
     view! { class=style,
         <div class="innerWrap" class:enlarge=enlarge on:click=click>
             <img src=move || {
-                relative_url(&format!("/api/experience/{}/cover/small", id())).unwrap().to_string()
+                relative_url(
+                        &format!(
+                            "/api/experience/{}/cover/small?v={}",
+                            id(),
+                            cover_version.map(|v| v()).unwrap_or_default(),
+                        ),
+                    )
+                    .unwrap()
+                    .to_string()
             } />
             <a class="textWrap">{name}</a>
         </div>
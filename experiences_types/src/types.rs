@@ -1,6 +1,12 @@
 use {
+    chrono::{DateTime, Utc},
     serde::{Deserialize, Serialize},
-    timeline_types::timing::Timing,
+    std::collections::{BTreeMap, HashMap, HashSet},
+    timeline_types::{
+        api::CompressedEvent,
+        available_plugins::AvailablePlugins,
+        timing::{TimeRange, Timing},
+    },
 };
 
 #[cfg_attr(feature = "client", derive(Deserialize))]
@@ -8,6 +14,60 @@ use {
 pub struct ExperienceConnection {
     pub id: String,
     pub name: String,
+    pub kind: ConnectionKind,
+    pub details: ConnectionDetails,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub enum ConnectionKind {
+    Peer,
+    Parent,
+    Child,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum ConnectionRelationship {
+    #[default]
+    Related,
+    FollowedBy,
+    PrecededBy,
+    SamePeople,
+    SamePlace,
+}
+
+impl ConnectionRelationship {
+    pub const ALL: [ConnectionRelationship; 5] = [
+        ConnectionRelationship::Related,
+        ConnectionRelationship::FollowedBy,
+        ConnectionRelationship::PrecededBy,
+        ConnectionRelationship::SamePeople,
+        ConnectionRelationship::SamePlace,
+    ];
+
+    pub fn inverse(&self) -> ConnectionRelationship {
+        match self {
+            ConnectionRelationship::FollowedBy => ConnectionRelationship::PrecededBy,
+            ConnectionRelationship::PrecededBy => ConnectionRelationship::FollowedBy,
+            v => v.clone(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConnectionRelationship::Related => "Related",
+            ConnectionRelationship::FollowedBy => "Followed by",
+            ConnectionRelationship::PrecededBy => "Preceded by",
+            ConnectionRelationship::SamePeople => "Same people",
+            ConnectionRelationship::SamePlace => "Same place",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ConnectionDetails {
+    pub relationship: ConnectionRelationship,
+    pub label: Option<String>,
 }
 
 #[cfg_attr(feature = "client", derive(Deserialize))]
@@ -15,7 +75,78 @@ pub struct ExperienceConnection {
 pub struct ExperienceConnectionResponse {
     pub connections: Vec<ExperienceConnection>,
     pub experience_name: String,
+    pub experience_time: Option<Timing>,
+    pub public: bool,
+    pub editable: bool,
+    pub owner: bool,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
     pub public: bool,
+    pub time: Option<Timing>,
+    pub cover: String,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: ConnectionKind,
+    pub details: ConnectionDetails,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExperienceSubgraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExperienceStats {
+    pub events: HashMap<AvailablePlugins, usize>,
+    pub favorites: usize,
+    pub time: Option<Timing>,
+    pub events_per_day: BTreeMap<String, usize>,
+    pub connections: usize,
+    pub size: u64,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct Memories {
+    pub date: String,
+    pub experiences: Vec<ExperienceMemory>,
+    pub events: Vec<EventMemory>,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExperienceMemory {
+    pub id: String,
+    pub name: String,
+    pub time: Timing,
+    pub years_ago: i32,
+    pub cover: String,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct EventMemory {
+    pub experience_id: String,
+    pub experience_name: String,
+    pub event_id: String,
+    pub plugin: AvailablePlugins,
+    pub title: String,
+    pub time: Timing,
+    pub years_ago: i32,
+    pub cover: String,
 }
 
 pub use timeline_types::api::ExperiencesHostname;
@@ -30,4 +161,291 @@ pub enum CompressedExperienceEvent {
 pub struct CreateExperienceRequest {
     pub name: String,
     pub time: Timing,
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperienceTemplate {
+    pub name: String,
+    pub name_pattern: String,
+    pub tags: Vec<String>,
+    pub public: bool,
+    pub metadata: HashMap<String, String>,
+    pub connections: Vec<String>,
+    #[serde(default)]
+    pub owner: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveTemplateRequest {
+    pub experience_id: String,
+    pub name: String,
+    pub name_pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExperienceDetails {
+    pub tags: Vec<String>,
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SuggestionsRequest {
+    pub range: TimeRange,
+    #[serde(default)]
+    pub gap_hours: Option<u32>,
+    #[serde(default)]
+    pub max_distance_km: Option<f64>,
+    #[serde(default)]
+    pub min_events: Option<usize>,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExperienceSuggestion {
+    pub name: String,
+    pub time: Timing,
+    pub events: Vec<SuggestedEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestedEvent {
+    pub plugin: AvailablePlugins,
+    pub event: CompressedEvent,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AcceptSuggestionRequest {
+    pub name: String,
+    pub time: Timing,
+    pub events: Vec<SuggestedEvent>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PullEventsRequest {
+    pub time: Timing,
+    #[serde(default)]
+    pub plugins: HashSet<AvailablePlugins>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RefreshRequest {
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct RefreshReport {
+    pub experience_id: String,
+    pub changed: Vec<EventRefresh>,
+    pub missing: Vec<String>,
+    pub applied: bool,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRefresh {
+    pub event_id: String,
+    pub plugin: AvailablePlugins,
+    pub old: CompressedEvent,
+    pub new: CompressedEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExperienceChange {
+    Created {
+        experience_id: String,
+    },
+    Edited {
+        experience_id: String,
+    },
+    Deleted {
+        experience_id: String,
+    },
+    Connected {
+        experience_id: String,
+        connected_id: String,
+    },
+    Disconnected {
+        experience_id: String,
+        connected_id: String,
+    },
+    EventDeleted {
+        experience_id: String,
+        event_id: String,
+    },
+    VisibilityChanged {
+        experience_id: String,
+        public: bool,
+    },
+    CoverRegenerated {
+        experience_id: String,
+    },
+    NavigatorPositionChanged {
+        experience_id: String,
+        user: String,
+    },
+}
+
+impl ExperienceChange {
+    pub fn experience_id(&self) -> &str {
+        match self {
+            ExperienceChange::Created { experience_id }
+            | ExperienceChange::Edited { experience_id }
+            | ExperienceChange::Deleted { experience_id }
+            | ExperienceChange::Connected { experience_id, .. }
+            | ExperienceChange::Disconnected { experience_id, .. }
+            | ExperienceChange::EventDeleted { experience_id, .. }
+            | ExperienceChange::VisibilityChanged { experience_id, .. }
+            | ExperienceChange::CoverRegenerated { experience_id }
+            | ExperienceChange::NavigatorPositionChanged { experience_id, .. } => experience_id,
+        }
+    }
+
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            ExperienceChange::CoverRegenerated { .. } => "cover_regenerated",
+            ExperienceChange::NavigatorPositionChanged { .. } => "navigator_position_changed",
+            _ => "experience_changed",
+        }
+    }
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct UserInfo {
+    pub name: String,
+    pub admin: bool,
+    pub groups: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoginRequest {
+    pub name: String,
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateUserRequest {
+    pub name: String,
+    pub password: String,
+    #[serde(default)]
+    pub admin: bool,
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareToken {
+    pub token: String,
+    pub created: DateTime<Utc>,
+    pub expires: Option<DateTime<Utc>>,
+    pub max_views: Option<u32>,
+    pub views: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateShareRequest {
+    #[serde(default)]
+    pub expires_in_hours: Option<u32>,
+    #[serde(default)]
+    pub max_views: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AccessRole {
+    Viewer,
+    Editor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccessSubject {
+    User(String),
+    Group(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessEntry {
+    pub subject: AccessSubject,
+    pub role: AccessRole,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TokenScope {
+    ReadOnly,
+    AppendOnly,
+    Admin,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub scope: TokenScope,
+    pub created: DateTime<Utc>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scope: TokenScope,
+}
+
+#[cfg_attr(feature = "client", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiToken {
+    pub info: ApiTokenInfo,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    Create,
+    Append,
+    Delete,
+    Favorite,
+    Visibility,
+    Details,
+    AccessList,
+    Connection,
+    Parent,
+    Refresh,
+    Template,
+    Share,
+    Token,
+    User,
+    Backup,
+    Restore,
+    Login,
+    Logout,
+    AuthFailure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub action: AuditAction,
+    pub actor: Option<String>,
+    pub ip: Option<String>,
+    pub experience_id: Option<String>,
+    pub details: Option<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct AuditQuery {
+    #[serde(default)]
+    pub experience_id: Option<String>,
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    pub action: Option<AuditAction>,
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<usize>,
 }
@@ -1,16 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use {
     experiences_navigator_lib::{
-        api::api_request,
+        api::{api_request, use_changes},
         navigator::StandaloneNavigator,
         wrappers::{Band, Error, Info, StyledView},
     },
+    chrono::{DateTime, Local, NaiveDateTime, Utc},
     leptos::*,
     link::timeline_frontend_lib::{events_display::EventsViewer, plugin_manager::PluginManager},
-    shared::types::{Experience, ExperienceEvent, PluginExperienceEvent},
+    shared::types::{
+        Experience, ExperienceEvent, PluginExperienceEvent, PullEventsRequest, RefreshReport,
+        RefreshRequest,
+    },
     std::sync::Arc,
-    timeline_types::available_plugins::AvailablePlugins,
+    timeline_types::{
+        available_plugins::AvailablePlugins,
+        timing::{TimeRange, Timing},
+    },
 };
 
 #[component]
@@ -21,6 +28,15 @@ pub fn Experience(#[prop(into)] id: MaybeSignal<String>) -> impl IntoView {
             .map(|v| (id, v))
     });
 
+    let changes = use_changes();
+    create_effect(move |_| {
+        if let Some(change) = changes() {
+            if change.experience_id() == id.get_untracked() {
+                experience.refetch();
+            }
+        }
+    });
+
     view! {
         <Suspense fallback=move || {
             view! { <Info>Loading</Info> }
@@ -62,6 +78,9 @@ pub fn ExperienceView(
     let plugin_manager = use_context::<PluginManager>()
         .expect("Plugin manager was not provided as context! Not good, not recoverable.");
 
+    let pull_id = id.clone();
+    let pull_experience = experience.clone();
+
     type GenTypeParam2 = impl Fn(PluginExperienceEvent, Box<dyn Fn()>) -> View + Clone;
 
     let t: GenTypeParam2 = move |event: PluginExperienceEvent, close_callback| {
@@ -70,10 +89,13 @@ pub fn ExperienceView(
         let close_callback_2 = close_callback.clone();
         let close_callback_3 = close_callback.clone();
         let close_callback_4 = close_callback.clone();
+        let close_callback_5 = close_callback.clone();
         let event_2 = event.clone();
         let event_3 = event.clone();
+        let event_4 = event.clone();
         let id = id.clone();
         let id_2 = id.clone();
+        let id_3 = id.clone();
 
         let (expanded, write_expanded) = create_signal(false);
 
@@ -155,6 +177,45 @@ pub fn ExperienceView(
                             }
                         } />
                     </Band>
+                    <Band
+                        color="var(--accentColor3)".to_string()
+                        click=Callback::new(move |_| {
+                            spawn_local({
+                                let id = id_3();
+                                let close_callback = close_callback_5.clone();
+                                let event = event_4.clone();
+                                async move {
+                                    close_callback();
+                                    if let Err(e) = experiences_navigator_lib::api::api_request::<
+                                        (),
+                                        _,
+                                    >(
+                                            &format!("/experience/{}/private", id),
+                                            &shared::types::EventPrivacyRequest {
+                                                event_id: event.1.id,
+                                                private: !event.1.private,
+                                            },
+                                        )
+                                        .await
+                                    {
+                                        window()
+                                            .alert_with_message(
+                                                &format!("Unable to change event privacy: {}", e),
+                                            )
+                                            .unwrap();
+                                    }
+                                    reload(());
+                                }
+                            });
+                        })
+                    >
+
+                        <img src=if event.1.private {
+                            "/icons/private.svg"
+                        } else {
+                            "/icons/public.svg"
+                        } />
+                    </Band>
                 </div>
                 <div on:click=move |_| write_expanded(true)>
                     <StandaloneNavigator expanded selected_experience=selected_experience />
@@ -195,6 +256,8 @@ pub fn ExperienceView(
     };
 
     view! {
+        <PullEvents id=pull_id.clone() experience=pull_experience reload />
+        <RefreshEvents id=pull_id reload />
         <EventsViewer<
         PluginExperienceEvent,
         GenTypeParam2,
@@ -218,3 +281,217 @@ pub fn ExperienceView(
         />
     }
 }
+
+#[component]
+fn PullEvents(
+    #[prop(into)] id: MaybeSignal<String>,
+    #[prop(into)] experience: MaybeSignal<Experience>,
+    reload: Callback<(), ()>,
+) -> impl IntoView {
+    let (expanded, write_expanded) = create_signal(false);
+    let (start, write_start) = create_signal(String::new());
+    let (end, write_end) = create_signal(String::new());
+    let plugins = create_rw_signal(HashSet::new());
+
+    let range = Signal::derive(move || match (local_time(&start()), local_time(&end())) {
+        (Some(start), Some(end)) if start <= end => Some(TimeRange { start, end }),
+        _ => None,
+    });
+
+    let timeline_plugins = create_resource(
+        {
+            let id = id.clone();
+            move || (id.get(), range())
+        },
+        |(id, range)| async move {
+            match range {
+                Some(range) => api_request::<Vec<AvailablePlugins>, _>(
+                    &format!("/experience/{}/pull_events/plugins", id),
+                    &range,
+                )
+                .await
+                .unwrap_or_default(),
+                None => Vec::new(),
+            }
+        },
+    );
+
+    let available_plugins = Signal::derive(move || {
+        let mut available = experience()
+            .events
+            .into_keys()
+            .filter(|v| *v != AvailablePlugins::timeline_plugin_experience)
+            .collect::<Vec<_>>();
+        for plugin in timeline_plugins().unwrap_or_default() {
+            if !available.contains(&plugin) {
+                available.push(plugin);
+            }
+        }
+        available
+    });
+
+    let pull = create_action(move |time: &Timing| {
+        let request = PullEventsRequest {
+            time: time.clone(),
+            plugins: plugins.get_untracked(),
+        };
+        let id = id.get_untracked();
+        async move {
+            let res =
+                api_request::<Vec<String>, _>(&format!("/experience/{}/pull_events", id), &request)
+                    .await;
+            if res.is_ok() {
+                reload(());
+            }
+            res
+        }
+    });
+
+    let submit = move |_| {
+        match range() {
+            Some(range) => pull.dispatch(Timing::Range(range)),
+            None => window()
+                .alert_with_message("Please select a valid time range")
+                .unwrap(),
+        }
+    };
+
+    view! {
+        <Band click=Callback::new(move |_| write_expanded.update(|v| *v = !*v))>
+            Pull events from timeline
+        </Band>
+        <div
+            style="flex-direction: column; gap: var(--contentSpacing); padding: var(--contentSpacing); color: var(--lightColor)"
+            style:display=move || if expanded() { "flex" } else { "none" }
+        >
+            <div style="display: flex; flex-direction: row; gap: var(--contentSpacing)">
+                <input
+                    type="datetime-local"
+                    on:change=move |e| write_start(event_target_value(&e))
+                />
+                <input type="datetime-local" on:change=move |e| write_end(event_target_value(&e)) />
+            </div>
+            {move || {
+                available_plugins()
+                    .into_iter()
+                    .map(|plugin| {
+                        let plugin_2 = plugin.clone();
+                        view! {
+                            <label>
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || plugins.with(|v| v.contains(&plugin))
+                                    on:change={
+                                        let plugin = plugin_2.clone();
+                                        move |e| {
+                                            let plugin = plugin.clone();
+                                            plugins
+                                                .update(|v| {
+                                                    if event_target_checked(&e) {
+                                                        v.insert(plugin);
+                                                    } else {
+                                                        v.remove(&plugin);
+                                                    }
+                                                })
+                                        }
+                                    }
+                                />
+                                {format!("{:?}", plugin_2)}
+                            </label>
+                        }
+                    })
+                    .collect_view()
+            }}
+            <Band click=Callback::new(submit)>Pull</Band>
+            {move || match pull.value()() {
+                Some(Ok(v)) => view! { <Info>{format!("Added {} events", v.len())}</Info> }.into_view(),
+                Some(Err(e)) => {
+                    view! { <Error>Unable to pull events: {e.to_string()}</Error> }.into_view()
+                }
+                None => view! {}.into_view(),
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn RefreshEvents(#[prop(into)] id: MaybeSignal<String>, reload: Callback<(), ()>) -> impl IntoView {
+    let refresh = create_action(move |apply: &bool| {
+        let request = RefreshRequest { apply: *apply };
+        let id = id.get_untracked();
+        async move {
+            let res =
+                api_request::<RefreshReport, _>(&format!("/experience/{}/refresh", id), &request)
+                    .await;
+            if res.as_ref().is_ok_and(|v| v.applied) {
+                reload(());
+            }
+            res
+        }
+    });
+
+    view! {
+        <Band click=Callback::new(move |_| refresh.dispatch(false))>
+            Check timeline for changes
+        </Band>
+        {move || {
+            if refresh.pending()() {
+                return view! { <Info>Loading</Info> }.into_view();
+            }
+            match refresh.value()() {
+                None => view! {}.into_view(),
+                Some(Ok(report)) if report.applied => {
+                    view! { <Info>{format!("Updated {} events", report.changed.len())}</Info> }
+                        .into_view()
+                }
+                Some(Ok(report)) if report.changed.is_empty() => {
+                    view! {
+                        <Info>
+                            {format!(
+                                "All events are up to date ({} not found in timeline)",
+                                report.missing.len(),
+                            )}
+                        </Info>
+                    }
+                        .into_view()
+                }
+                Some(Ok(report)) => {
+                    view! {
+                        <div style="display: flex; flex-direction: column; gap: var(--contentSpacing); padding: var(--contentSpacing); color: var(--lightColor)">
+                            {report
+                                .changed
+                                .into_iter()
+                                .map(|change| {
+                                    view! {
+                                        <div>
+                                            <s>{change.old.title.clone()}</s>
+                                            " → "
+                                            <b>{change.new.title.clone()}</b>
+                                            {(change.old.data != change.new.data)
+                                                .then_some(" (data changed)")}
+                                        </div>
+                                    }
+                                })
+                                .collect_view()}
+                        </div>
+                        <Band click=Callback::new(move |_| refresh.dispatch(true))>
+                            Apply changes
+                        </Band>
+                    }
+                        .into_view()
+                }
+                Some(Err(e)) => {
+                    view! { <Error>Unable to refresh events: {e.to_string()}</Error> }.into_view()
+                }
+            }
+        }}
+    }
+}
+
+fn local_time(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .ok()?
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|v| v.with_timezone(&Utc))
+}
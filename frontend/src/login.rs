@@ -0,0 +1,64 @@
+use {
+    experiences_navigator_lib::{
+        api::api_request,
+        wrappers::{Band, Error},
+    },
+    leptos::*,
+    shared::types::{LoginRequest, UserInfo},
+    stylers::style,
+};
+
+#[component]
+pub fn Login(update_authentication: WriteSignal<i32>) -> impl IntoView {
+    let (name, write_name) = create_signal(String::new());
+    let (password, write_password) = create_signal(String::new());
+
+    let login = create_action(move |_: &()| {
+        let request = LoginRequest {
+            name: name.get_untracked(),
+            password: password.get_untracked(),
+        };
+        async move {
+            let res = api_request::<UserInfo, _>("/login", &request).await;
+            if res.is_ok() {
+                update_authentication.update(|v| *v += 1);
+            }
+            res
+        }
+    });
+
+    let style = style! {
+        .login {
+            display: flex;
+            flex-direction: column;
+            align-items: center;
+            gap: var(--contentSpacing);
+            padding: var(--contentSpacing);
+        }
+    };
+
+    view! { class=style,
+        <div class="login">
+            <input
+                type="text"
+                placeholder="User"
+                on:input=move |e| write_name(event_target_value(&e))
+            />
+            <input
+                type="password"
+                placeholder="Password"
+                on:input=move |e| write_password(event_target_value(&e))
+                on:keydown=move |e| {
+                    if e.key() == "Enter" {
+                        login.dispatch(())
+                    }
+                }
+            />
+            <Band click=Callback::new(move |_| login.dispatch(()))>Login</Band>
+            {move || match login.value()() {
+                Some(Err(e)) => view! { <Error>Unable to login: {e.to_string()}</Error> }.into_view(),
+                _ => view! {}.into_view(),
+            }}
+        </div>
+    }
+}
@@ -1,20 +1,21 @@
 #![feature(type_alias_impl_trait)]
 use {
     experiences_navigator_lib::{
-        api::api_request,
+        api::{api_request, provide_changes},
         navigator::{Navigator, NavigatorOutput},
         wrappers::{Error, Info, StyledView, TitleBar},
     },
     leptos::*,
     leptos_router::*,
-    link::timeline_frontend_lib::{
-        events_display::DisplayWithDay, plugin_manager::PluginManager, wrappers::Login,
-    },
+    link::timeline_frontend_lib::{events_display::DisplayWithDay, plugin_manager::PluginManager},
     shared::standalone_experience_types::types::ExperiencesHostname,
     timeline_types::api::TimelineHostname,
 };
 
 mod experience;
+mod login;
+mod memories;
+mod suggestions;
 
 fn main() {
     console_error_panic_hook::set_once();
@@ -26,10 +27,14 @@ fn main() {
 #[component]
 fn MainView() -> impl IntoView {
     provide_context(ExperiencesHostname(leptos::window().origin()));
+    provide_changes();
     view! {
         <Router>
             <Routes>
                 <Route path="/experience/:id" view=ExperienceView />
+                <Route path="/memories" view=memories::MemoriesView />
+                <Route path="/memories/:date" view=memories::MemoriesView />
+                <Route path="/suggestions" view=suggestions::SuggestionsView />
                 <Route path="/" view=Redirect />
                 <Route path="*not_found" view=NotFound />
             </Routes>
@@ -77,7 +82,7 @@ fn Redirect() -> impl IntoView {
                                         <Error>
                                             Error loading Navigator Position: {e.to_string()}
                                         </Error>
-                                        <Login update_authentication=write_update_authentication />
+                                        <login::Login update_authentication=write_update_authentication />
                                     }
                                         .into_view()
                                 }
@@ -130,7 +135,12 @@ fn ExperienceView() -> impl IntoView {
                             match timeline_url_error {
                                 None => {
                                     view! {
-                                        <TitleBar />
+                                        <TitleBar
+                                            subtitle=Some("On this day".to_string())
+                                            subtitle_click_callback=Callback::new(|_| {
+                                                use_navigate()("/memories", NavigateOptions::default())
+                                            })
+                                        />
                                         <div on:click=move |_| write_navigator_expanded(true)>
                                             <Navigator
                                                 experience=experience_id
@@ -0,0 +1,191 @@
+use {
+    experiences_navigator_lib::{
+        api::{api_request, relative_url},
+        wrappers::{Band, Error, Info, StyledView, TitleBar},
+    },
+    leptos::*,
+    leptos_router::*,
+    shared::types::{EventMemory, ExperienceMemory, Memories},
+    stylers::style,
+};
+
+#[component]
+pub fn MemoriesView() -> impl IntoView {
+    let params = use_params_map();
+    let date = Signal::derive(move || params().get("date").cloned());
+
+    let memories = create_resource(date, |date| async move {
+        match date {
+            Some(date) => api_request::<Memories, _>(&format!("/memories/{}", date), &()).await,
+            None => api_request::<Memories, _>("/memories", &()).await,
+        }
+    });
+
+    let style = style! {
+        .memories {
+            display: flex;
+            flex-direction: column;
+            gap: var(--contentSpacing);
+            padding: var(--contentSpacing);
+            overflow: auto;
+        }
+
+        .dateWrapper {
+            display: flex;
+            flex-direction: row;
+            justify-content: center;
+            gap: var(--contentSpacing);
+            color: var(--lightColor);
+        }
+
+        .grid {
+            display: grid;
+            grid-template-columns: repeat(auto-fill, minmax(150px, 1fr));
+            gap: var(--contentSpacing);
+        }
+    };
+
+    view! { class=style,
+        <StyledView>
+            <TitleBar subtitle=Some("On this day".to_string()) />
+            <Band click=Callback::new(|_| {
+                use_navigate()("/suggestions", NavigateOptions::default())
+            })>Suggest experiences</Band>
+            <Suspense fallback=move || {
+                view! { <Info>Loading</Info> }
+            }>
+                {move || {
+                    memories()
+                        .map(|memories| match memories {
+                            Ok(memories) => {
+                                let empty = memories.experiences.is_empty()
+                                    && memories.events.is_empty();
+                                view! { class=style,
+                                    <div class="memories">
+                                        <div class="dateWrapper">
+                                            <input
+                                                type="date"
+                                                value=memories.date.clone()
+                                                on:change=|e| {
+                                                    use_navigate()(
+                                                        &format!("/memories/{}", event_target_value(&e)),
+                                                        NavigateOptions::default(),
+                                                    )
+                                                }
+                                            />
+                                        </div>
+                                        {if empty {
+                                            view! { <Info>No memories for this day</Info> }
+                                                .into_view()
+                                        } else {
+                                            view! { class=style,
+                                                <div class="grid">
+                                                    {memories
+                                                        .experiences
+                                                        .into_iter()
+                                                        .map(|memory| view! { <ExperienceMemoryCard memory /> })
+                                                        .collect_view()}
+                                                </div>
+                                                <div class="grid">
+                                                    {memories
+                                                        .events
+                                                        .into_iter()
+                                                        .map(|memory| view! { <EventMemoryCard memory /> })
+                                                        .collect_view()}
+                                                </div>
+                                            }
+                                                .into_view()
+                                        }}
+                                    </div>
+                                }
+                                    .into_view()
+                            }
+                            Err(e) => {
+                                view! { <Error>Error loading memories: {e.to_string()}</Error> }
+                                    .into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </StyledView>
+    }
+}
+
+#[component]
+fn ExperienceMemoryCard(memory: ExperienceMemory) -> impl IntoView {
+    view! {
+        <MemoryCard
+            cover=memory.cover
+            title=memory.name
+            subtitle=years_ago(memory.years_ago)
+            experience_id=memory.id
+        />
+    }
+}
+
+#[component]
+fn EventMemoryCard(memory: EventMemory) -> impl IntoView {
+    view! {
+        <MemoryCard
+            cover=memory.cover
+            title=memory.title
+            subtitle=format!("{} - {}", memory.experience_name, years_ago(memory.years_ago))
+            experience_id=memory.experience_id
+        />
+    }
+}
+
+#[component]
+fn MemoryCard(cover: String, title: String, subtitle: String, experience_id: String) -> impl IntoView {
+    let style = style! {
+        .card {
+            display: flex;
+            flex-direction: column;
+            border-radius: 5px;
+            background-color: var(--accentColor3);
+            color: var(--lightColor);
+            overflow: hidden;
+        }
+
+        img {
+            width: 100%;
+            aspect-ratio: 1;
+            object-fit: cover;
+        }
+
+        .text {
+            padding: var(--contentSpacing);
+            word-wrap: break-word;
+        }
+
+        .subtitle {
+            font-size: 80%;
+        }
+    };
+
+    view! { class=style,
+        <div
+            class="card"
+            role="button"
+            on:click=move |_| {
+                use_navigate()(
+                    &format!("/experience/{}", experience_id),
+                    NavigateOptions::default(),
+                )
+            }
+        >
+            <img src=relative_url(&cover).unwrap().to_string() />
+            <div class="text">
+                <div>{title}</div>
+                <div class="subtitle">{subtitle}</div>
+            </div>
+        </div>
+    }
+}
+
+fn years_ago(years: i32) -> String {
+    match years {
+        1 => "1 year ago".to_string(),
+        v => format!("{} years ago", v),
+    }
+}
@@ -0,0 +1,173 @@
+use {
+    chrono::{Duration, NaiveDate, NaiveTime},
+    experiences_navigator_lib::{
+        api::api_request,
+        wrappers::{Band, Error, Info, StyledView, TitleBar},
+    },
+    leptos::*,
+    leptos_router::*,
+    shared::types::{AcceptSuggestionRequest, ExperienceSuggestion, SuggestionsRequest},
+    stylers::style,
+    timeline_types::timing::TimeRange,
+};
+
+#[component]
+pub fn SuggestionsView() -> impl IntoView {
+    let (start, write_start) = create_signal(String::new());
+    let (end, write_end) = create_signal(String::new());
+
+    let suggestions = create_action(|range: &TimeRange| {
+        let request = SuggestionsRequest {
+            range: range.clone(),
+            gap_hours: None,
+            max_distance_km: None,
+            min_events: None,
+        };
+        async move { api_request::<Vec<ExperienceSuggestion>, _>("/suggestions", &request).await }
+    });
+
+    let search = move |_| {
+        if let (Ok(start), Ok(end)) = (
+            NaiveDate::parse_from_str(&start(), "%Y-%m-%d"),
+            NaiveDate::parse_from_str(&end(), "%Y-%m-%d"),
+        ) {
+            suggestions.dispatch(TimeRange {
+                start: start.and_time(NaiveTime::MIN).and_utc(),
+                end: (end + Duration::days(1)).and_time(NaiveTime::MIN).and_utc(),
+            });
+        }
+    };
+
+    let style = style! {
+        .suggestions {
+            display: flex;
+            flex-direction: column;
+            gap: var(--contentSpacing);
+            padding: var(--contentSpacing);
+            overflow: auto;
+        }
+
+        .rangeWrapper {
+            display: flex;
+            flex-direction: row;
+            justify-content: center;
+            gap: var(--contentSpacing);
+        }
+    };
+
+    view! { class=style,
+        <StyledView>
+            <TitleBar subtitle=Some("Suggestions".to_string()) />
+            <div class="suggestions">
+                <div class="rangeWrapper">
+                    <input type="date" on:change=move |e| write_start(event_target_value(&e)) />
+                    <input type="date" on:change=move |e| write_end(event_target_value(&e)) />
+                </div>
+                <Band click=Callback::new(search)>Find suggestions</Band>
+                {move || {
+                    if suggestions.pending()() {
+                        return view! { <Info>Loading</Info> }.into_view();
+                    }
+                    match suggestions.value()() {
+                        None => view! {}.into_view(),
+                        Some(Ok(v)) if v.is_empty() => {
+                            view! { <Info>No suggestions for this range</Info> }.into_view()
+                        }
+                        Some(Ok(v)) => {
+                            v.into_iter()
+                                .map(|suggestion| view! { <Suggestion suggestion /> })
+                                .collect_view()
+                        }
+                        Some(Err(e)) => {
+                            view! { <Error>Error loading suggestions: {e.to_string()}</Error> }
+                                .into_view()
+                        }
+                    }
+                }}
+            </div>
+        </StyledView>
+    }
+}
+
+#[component]
+fn Suggestion(suggestion: ExperienceSuggestion) -> impl IntoView {
+    let (name, write_name) = create_signal(suggestion.name.clone());
+    let selected = create_rw_signal(vec![true; suggestion.events.len()]);
+
+    let time = suggestion.time.clone();
+    let events = suggestion.events.clone();
+    let accept = create_action(move |_: &()| {
+        let request = AcceptSuggestionRequest {
+            name: name.get_untracked(),
+            time: time.clone(),
+            events: events
+                .iter()
+                .zip(selected.get_untracked())
+                .filter(|(_, selected)| *selected)
+                .map(|(event, _)| event.clone())
+                .collect(),
+        };
+        async move {
+            let res = api_request::<String, _>("/suggestions/accept", &request).await;
+            if let Ok(id) = &res {
+                use_navigate()(&format!("/experience/{}", id), NavigateOptions::default());
+            }
+            res
+        }
+    });
+
+    let style = style! {
+        .suggestion {
+            display: flex;
+            flex-direction: column;
+            gap: var(--contentSpacing);
+            padding: var(--contentSpacing);
+            border-radius: 5px;
+            background-color: var(--accentColor3Light);
+            color: var(--lightColor);
+        }
+
+        .event {
+            display: flex;
+            flex-direction: row;
+            align-items: center;
+            gap: var(--contentSpacing);
+        }
+    };
+
+    view! { class=style,
+        <div class="suggestion">
+            <input
+                type="text"
+                prop:value=name
+                on:input=move |e| write_name(event_target_value(&e))
+            />
+            {suggestion
+                .events
+                .iter()
+                .enumerate()
+                .map(|(index, event)| {
+                    view! { class=style,
+                        <label class="event">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || selected.with(|v| v[index])
+                                on:change=move |e| {
+                                    selected.update(|v| v[index] = event_target_checked(&e))
+                                }
+                            />
+                            {event.event.title.clone()}
+                        </label>
+                    }
+                })
+                .collect_view()}
+            <Band click=Callback::new(move |_| accept.dispatch(()))>Create experience</Band>
+            {move || match accept.value()() {
+                Some(Err(e)) => {
+                    view! { <Error>Error creating experience: {e.to_string()}</Error> }.into_view()
+                }
+                _ => view! {}.into_view(),
+            }}
+        </div>
+    }
+}
@@ -1,22 +1,42 @@
 pub use {
-    crate::config::Config,
+    crate::{config::Config, experience_manager::ExperienceManager},
+    chrono::{DateTime, Duration, Utc},
     rocket::{
         get,
-        http::{ContentType, CookieJar, Status},
+        http::{ContentType, Cookie, CookieJar, SameSite, Status},
         post,
+        request::{FromRequest, Outcome, Request},
         response::status,
         serde::json::Json,
         State,
     },
+    server_api::{
+        access::{access_level, can_view, owns, redact_private_events, AccessLevel},
+        audit::{entry, AuditLog},
+        positions::PositionManager,
+        rate_limit::RateLimiter,
+        shares::ShareManager,
+        tokens::TokenManager,
+        users::{User, UserManager},
+    },
     timeline_types::api::{APIError, APIResult, CompressedEvent},
     timeline_types::available_plugins::AvailablePlugins,
     shared::{
-        standalone_experience_types::types::{ExperienceConnection, ExperienceConnectionResponse},
+        standalone_experience_types::types::{
+            ConnectionKind, ExperienceConnection, ExperienceConnectionResponse,
+        },
         types::{
-            CreateExperienceRequest, Experience, ExperienceError, ExperienceEvent, FavoriteRequest,
+            AccessEntry, CompressedExperienceEvent, CreateExperienceRequest, Experience,
+            AuditAction, EventPrivacyRequest, ExperienceChange, ExperienceError, ExperienceEvent,
+            LoginRequest,
+            TokenScope, UserInfo,
+            ConnectionDetails, ExperienceDetails, ExperienceStats, ExperienceTemplate,
+            FavoriteRequest,
+            PullEventsRequest, RefreshReport, RefreshRequest, SaveTemplateRequest,
         },
     },
-    tokio::{fs::File, sync::RwLock},
+    std::{collections::HashSet, net::IpAddr, sync::Arc},
+    tokio::fs::File,
 };
 
 pub mod experiences {
@@ -24,55 +44,106 @@ pub mod experiences {
         super::*,
         crate::{config::Config, experience_manager::ExperienceManager},
         image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder},
-        std::{path::PathBuf, thread},
+        raqote::DrawTarget,
+        server_api::timeline::TimelineClient,
+        std::{future::Future, path::PathBuf, thread},
+        timeline_types::timing::TimeRange,
     };
 
     #[post("/experience/<id>")]
     pub async fn get_experience(
         id: &str,
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
     ) -> status::Custom<Json<APIResult<Experience>>> {
-        let experience = match experience_manager.get_experience(id).await {
-            Ok(v) => v,
-            Err(e) => {
-                return match &e {
-                    ExperienceError::NotFound(_) => {
-                        status::Custom(Status::NotFound, Json(Err(e.into())))
-                    }
-                    _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        match authorize(&caller, experience_manager, id, AccessLevel::Viewer).await {
+            Ok(mut v) => {
+                if access_level(&v, caller.user.as_ref()) < AccessLevel::Owner {
+                    v.acl.clear();
+                    redact_private_events(&mut v);
                 }
+                redact_hidden_connections(&caller, experience_manager, id, &mut v).await;
+                status::Custom(Status::Accepted, Json(Ok(v)))
             }
-        };
+            Err((status, e)) => status::Custom(status, Json(Err(e))),
+        }
+    }
 
-        if experience.public {
-            status::Custom(Status::Accepted, Json(Ok(experience)))
-        } else {
-            match auth(cookies, config) {
-                Ok(_) => status::Custom(Status::Accepted, Json(Ok(experience))),
-                Err(e) => status::Custom(Status::Unauthorized, Json(Err(e))),
-            }
+    #[post("/experience/<id>/stats")]
+    pub async fn get_stats(
+        id: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<ExperienceStats>>> {
+        let mut experience =
+            match authorize(&caller, experience_manager, id, AccessLevel::Viewer).await {
+                Ok(v) => v,
+                Err((status, e)) => return status::Custom(status, Json(Err(e))),
+            };
+        let redacted = access_level(&experience, caller.user.as_ref()) < AccessLevel::Owner;
+        if redacted {
+            redact_private_events(&mut experience);
+        }
+        redact_hidden_connections(&caller, experience_manager, id, &mut experience).await;
+
+        match experience_manager.get_stats(id, &experience, redacted).await {
+            Ok(v) => status::Custom(Status::Ok, Json(Ok(v))),
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
         }
     }
 
     #[post("/experience/create", data = "<request>")]
     pub async fn create_experience(
         request: Json<CreateExperienceRequest>,
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
     ) -> status::Custom<Json<APIResult<String>>> {
-        if let Err(e) = auth(cookies, config) {
-            return status::Custom(Status::Unauthorized, Json(Err(e)));
+        let user = match auth_scoped(&caller, TokenScope::AppendOnly) {
+            Ok(v) => v,
+            Err((status, e)) => return status::Custom(status, Json(Err(e))),
+        };
+
+        if let Some(template) = &request.template {
+            match experience_manager.get_template(template).await {
+                Ok(v) if owns(&v.owner, user) => {}
+                Ok(_) => {
+                    return status::Custom(
+                        Status::NotFound,
+                        Json(Err(ExperienceError::NotFound(template.clone()).into())),
+                    )
+                }
+                Err(e) => {
+                    return match &e {
+                        ExperienceError::NotFound(_) => {
+                            status::Custom(Status::NotFound, Json(Err(e.into())))
+                        }
+                        _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+                    }
+                }
+            }
         }
 
         match experience_manager
-            .create_experience(request.name.clone(), request.time.clone())
+            .create_experience(
+                request.name.clone(),
+                request.time.clone(),
+                request.template.as_deref(),
+                Some(user.name.clone()),
+            )
             .await
         {
-            Ok(v) => status::Custom(Status::Ok, Json(Ok(v))),
-            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            Ok(v) => {
+                caller
+                    .audit(AuditAction::Create, Some(&v), Some(request.name.clone()))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(v)))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
         }
     }
 
@@ -80,19 +151,65 @@ pub mod experiences {
     pub async fn favorite_event(
         id: &str,
         request: Json<FavoriteRequest>,
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
     ) -> status::Custom<Json<APIResult<()>>> {
-        if let Err(e) = auth(cookies, config) {
-            return status::Custom(Status::Unauthorized, Json(Err(e)));
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Editor).await
+        {
+            return status::Custom(status, Json(Err(e)));
         }
 
         match experience_manager
             .favorite_event(id, &request.event_id, request.favorite)
             .await
         {
-            Ok(_) => status::Custom(Status::Ok, Json(Ok(()))),
+            Ok(_) => {
+                caller
+                    .audit(
+                        AuditAction::Favorite,
+                        Some(id),
+                        Some(format!("{} {}", request.event_id, request.favorite)),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/experience/<id>/private", data = "<request>")]
+    pub async fn set_event_private(
+        id: &str,
+        request: Json<EventPrivacyRequest>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Owner).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match experience_manager
+            .set_event_private(id, &request.event_id, request.private)
+            .await
+        {
+            Ok(_) => {
+                caller
+                    .audit(
+                        AuditAction::Visibility,
+                        Some(id),
+                        Some(format!("{} private {}", request.event_id, request.private)),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
             Err(e) => match &e {
                 ExperienceError::NotFound(_) => {
                     status::Custom(Status::NotFound, Json(Err(e.into())))
@@ -106,16 +223,26 @@ pub mod experiences {
     pub async fn delete_event(
         id: &str,
         request: Json<String>,
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
     ) -> status::Custom<Json<APIResult<Option<(AvailablePlugins, ExperienceEvent)>>>> {
-        if let Err(e) = auth(cookies, config) {
-            return status::Custom(Status::Unauthorized, Json(Err(e)));
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Editor).await
+        {
+            return status::Custom(status, Json(Err(e)));
         }
 
         match experience_manager.delete_event(id, &request).await {
-            Ok(v) => status::Custom(Status::Ok, Json(Ok(v))),
+            Ok(v) => {
+                caller
+                    .audit(
+                        AuditAction::Delete,
+                        Some(id),
+                        v.as_ref().map(|(plugin, event)| format!("{:?} {}", plugin, event.id)),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(v)))
+            }
             Err(e) => match &e {
                 ExperienceError::NotFound(_) => {
                     status::Custom(Status::NotFound, Json(Err(e.into())))
@@ -129,19 +256,123 @@ pub mod experiences {
     pub async fn change_visibility(
         id: &str,
         request: Json<bool>,
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
     ) -> status::Custom<Json<APIResult<()>>> {
-        if let Err(e) = auth(cookies, config) {
-            return status::Custom(Status::Unauthorized, Json(Err(e)));
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Owner).await
+        {
+            return status::Custom(status, Json(Err(e)));
         }
 
         match experience_manager
             .set_experience_visibility(id, *request)
             .await
         {
-            Ok(_) => status::Custom(Status::Ok, Json(Ok(()))),
+            Ok(_) => {
+                caller
+                    .audit(
+                        AuditAction::Visibility,
+                        Some(id),
+                        Some(if *request { "public" } else { "private" }.to_string()),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/experience/<id>/acl")]
+    pub async fn get_acl(
+        id: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<Vec<AccessEntry>>>> {
+        match authorize(&caller, experience_manager, id, AccessLevel::Owner).await {
+            Ok(v) => status::Custom(Status::Ok, Json(Ok(v.acl))),
+            Err((status, e)) => status::Custom(status, Json(Err(e))),
+        }
+    }
+
+    #[post("/experience/<id>/acl/set", data = "<request>")]
+    pub async fn set_acl(
+        id: &str,
+        request: Json<Vec<AccessEntry>>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Owner).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        let details = format!("{} entries", request.len());
+        match experience_manager.set_acl(id, request.into_inner()).await {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::AccessList, Some(id), Some(details))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/experience/<id>/details")]
+    pub async fn get_details(
+        id: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<ExperienceDetails>>> {
+        match authorize(&caller, experience_manager, id, AccessLevel::Viewer).await {
+            Ok(v) => status::Custom(
+                Status::Ok,
+                Json(Ok(ExperienceDetails {
+                    tags: v.tags,
+                    metadata: v.metadata,
+                })),
+            ),
+            Err((status, e)) => status::Custom(status, Json(Err(e))),
+        }
+    }
+
+    #[post("/experience/<id>/details/set", data = "<request>")]
+    pub async fn set_details(
+        id: &str,
+        request: Json<ExperienceDetails>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Editor).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        let details = format!(
+            "{} tags, {} metadata entries",
+            request.tags.len(),
+            request.metadata.len()
+        );
+        match experience_manager.set_details(id, request.into_inner()).await {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::Details, Some(id), Some(details))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
             Err(e) => match &e {
                 ExperienceError::NotFound(_) => {
                     status::Custom(Status::NotFound, Json(Err(e.into())))
@@ -155,20 +386,161 @@ pub mod experiences {
     pub async fn append_event(
         id: &str,
         request: Json<(AvailablePlugins, CompressedEvent)>,
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
     ) -> status::Custom<Json<APIResult<String>>> {
-        if let Err(e) = auth(cookies, config) {
-            return status::Custom(Status::Unauthorized, Json(Err(e)));
+        if let Err((status, e)) = authorize_scoped(
+            &caller,
+            experience_manager,
+            id,
+            AccessLevel::Editor,
+            TokenScope::AppendOnly,
+        )
+        .await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+        if let (AvailablePlugins::timeline_plugin_experience, event) = &*request
+            && let Ok(CompressedExperienceEvent::Experience(connected_id)) =
+                serde_json::from_value(event.data.clone())
+            && let Err((status, e)) = authorize_scoped(
+                &caller,
+                experience_manager,
+                &connected_id,
+                AccessLevel::Editor,
+                TokenScope::AppendOnly,
+            )
+            .await
+        {
+            return status::Custom(status, Json(Err(e)));
         }
 
+        let plugin = request.0 .0.clone();
         match experience_manager.append_event(id, request.0).await {
+            Ok(v) => {
+                caller
+                    .audit(AuditAction::Append, Some(id), Some(format!("{:?} {}", plugin, v)))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(v)))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/experience/<id>/pull_events/plugins", data = "<range>")]
+    pub async fn pull_plugins(
+        id: &str,
+        range: Json<TimeRange>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+        timeline: &State<TimelineClient>,
+    ) -> status::Custom<Json<APIResult<Vec<AvailablePlugins>>>> {
+        if let Err((status, e)) = authorize_scoped(
+            &caller,
+            experience_manager,
+            id,
+            AccessLevel::Editor,
+            TokenScope::AppendOnly,
+        )
+        .await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match timeline.get_plugins(&range).await {
             Ok(v) => status::Custom(Status::Ok, Json(Ok(v))),
+            Err(e) => status::Custom(Status::BadGateway, Json(Err(e.into()))),
+        }
+    }
+
+    #[post("/experience/<id>/pull_events", data = "<request>")]
+    pub async fn pull_events(
+        id: &str,
+        request: Json<PullEventsRequest>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+        timeline: &State<TimelineClient>,
+    ) -> status::Custom<Json<APIResult<Vec<String>>>> {
+        if let Err((status, e)) = authorize_scoped(
+            &caller,
+            experience_manager,
+            id,
+            AccessLevel::Editor,
+            TokenScope::AppendOnly,
+        )
+        .await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match experience_manager
+            .pull_events(id, timeline, &request.time, &request.plugins)
+            .await
+        {
+            Ok(v) => {
+                caller
+                    .audit(
+                        AuditAction::Append,
+                        Some(id),
+                        Some(format!("Pulled {} events", v.len())),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(v)))
+            }
             Err(e) => match &e {
                 ExperienceError::NotFound(_) => {
                     status::Custom(Status::NotFound, Json(Err(e.into())))
                 }
+                ExperienceError::TimelineError(_) => {
+                    status::Custom(Status::BadGateway, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/experience/<id>/refresh", data = "<request>")]
+    pub async fn refresh_events(
+        id: &str,
+        request: Json<RefreshRequest>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+        timeline: &State<TimelineClient>,
+    ) -> status::Custom<Json<APIResult<RefreshReport>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Editor).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match experience_manager
+            .refresh_events(id, timeline, request.apply)
+            .await
+        {
+            Ok(v) => {
+                if v.applied {
+                    caller
+                        .audit(
+                            AuditAction::Refresh,
+                            Some(id),
+                            Some(format!("{} events changed", v.changed.len())),
+                        )
+                        .await;
+                }
+                status::Custom(Status::Ok, Json(Ok(v)))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                ExperienceError::TimelineError(_) => {
+                    status::Custom(Status::BadGateway, Json(Err(e.into())))
+                }
                 _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
             },
         }
@@ -178,28 +550,81 @@ pub mod experiences {
     pub async fn entire_experience_cover(
         id: &str,
         size: u32,
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
     ) -> status::Custom<Option<(ContentType, Vec<u8>)>> {
-        let experience = match experience_manager.get_experience(id).await {
-            Ok(v) => v,
-            Err(e) => {
-                return match &e {
-                    ExperienceError::NotFound(_) => status::Custom(Status::NotFound, None),
-                    _ => status::Custom(Status::InternalServerError, None),
-                }
-            }
-        };
+        let mut experience =
+            match authorize(&caller, experience_manager, id, AccessLevel::Viewer).await {
+                Ok(v) => v,
+                Err((status, _)) => return status::Custom(status, None),
+            };
+        if access_level(&experience, caller.user.as_ref()) < AccessLevel::Owner {
+            redact_private_events(&mut experience);
+        }
+        redact_hidden_connections(&caller, experience_manager, id, &mut experience).await;
 
-        if !experience.public
-            && let Err(_e) = auth(cookies, config)
-        {
-            return status::Custom(Status::Unauthorized, None);
+        let renderer = experience_manager.renderer.clone();
+
+        match render_png(size, move || async move {
+            renderer
+                .render_entire_experience(&experience, size as i32)
+                .await
+        }) {
+            Some(v) => status::Custom(Status::Ok, Some((ContentType::PNG, v))),
+            None => status::Custom(Status::InternalServerError, None),
+        }
+    }
+
+    #[get("/experience/<id>/event/<event_id>/cover")]
+    pub async fn event_cover(
+        id: &str,
+        event_id: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Option<(ContentType, Vec<u8>)>> {
+        let mut experience =
+            match authorize(&caller, experience_manager, id, AccessLevel::Viewer).await {
+                Ok(v) => v,
+                Err((status, _)) => return status::Custom(status, None),
+            };
+        if access_level(&experience, caller.user.as_ref()) < AccessLevel::Owner {
+            redact_private_events(&mut experience);
         }
+        redact_hidden_connections(&caller, experience_manager, id, &mut experience).await;
+
+        let (plugin, event) = match experience.events.into_iter().find_map(|(plugin, events)| {
+            events
+                .into_iter()
+                .find(|v| v.id == event_id)
+                .map(|v| (plugin, v.event))
+        }) {
+            Some(v) => v,
+            None => return status::Custom(Status::NotFound, None),
+        };
 
         let renderer = experience_manager.renderer.clone();
 
+        match render_png(EVENT_COVER_SIZE, move || async move {
+            renderer
+                .render_event(
+                    &plugin,
+                    &event,
+                    (EVENT_COVER_SIZE as i32, EVENT_COVER_SIZE as i32),
+                )
+                .await
+        }) {
+            Some(v) => status::Custom(Status::Ok, Some((ContentType::PNG, v))),
+            None => status::Custom(Status::InternalServerError, None),
+        }
+    }
+
+    const EVENT_COVER_SIZE: u32 = 300;
+
+    fn render_png<F, R>(size: u32, render: F) -> Option<Vec<u8>>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Future<Output = DrawTarget> + 'static,
+    {
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
@@ -212,10 +637,7 @@ pub mod experiences {
                 tokio::task::spawn_local(async move {
                     let mut image: Vec<u8> = Vec::new();
                     let encoder = PngEncoder::new(&mut image);
-                    let data = renderer
-                        .render_entire_experience(&experience, size as i32)
-                        .await
-                        .into_vec();
+                    let data = render().await.into_vec();
                     let data = data
                         .into_iter()
                         .flat_map(|v| {
@@ -238,9 +660,9 @@ pub mod experiences {
         if let Ok(outer) = image
             && let Ok(inner) = outer
         {
-            status::Custom(Status::Ok, Some((ContentType::PNG, inner)))
+            Some(inner)
         } else {
-            status::Custom(Status::InternalServerError, None)
+            None
         }
     }
 
@@ -249,24 +671,17 @@ pub mod experiences {
         id: &str,
         size: &str,
         config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
     ) -> status::Custom<Option<(ContentType, Result<File, std::io::Error>)>> {
-        let experience = match experience_manager.get_experience(id).await {
-            Ok(v) => v,
-            Err(e) => {
-                return match &e {
-                    ExperienceError::NotFound(_) => status::Custom(Status::NotFound, None),
-                    _ => status::Custom(Status::InternalServerError, None),
-                }
-            }
-        };
-
-        if !experience.public
-            && let Err(_e) = auth(cookies, config)
+        if let Err((status, _)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Viewer).await
         {
+            if status == Status::NotFound || status == Status::InternalServerError {
+                return status::Custom(status, None);
+            }
             return status::Custom(
-                Status::Unauthorized,
+                status,
                 Some((
                     ContentType::PNG,
                     File::open(PathBuf::from("../frontend/icons/logo.png")).await,
@@ -288,130 +703,1668 @@ pub mod experiences {
 pub mod navigator {
     use {
         super::*,
-        crate::{config::Config, experience_manager::ExperienceManager},
+        crate::experience_manager::{experience_time, ExperienceManager},
     };
 
-    pub struct NavigatorPosition(pub RwLock<String>);
-
     #[post("/navigator/<id>")]
     pub async fn get_connections(
         id: &str,
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
+        caller: Caller,
         experience_manager: &State<ExperienceManager>,
-        navigator_position: &State<NavigatorPosition>,
+        positions: &State<PositionManager>,
     ) -> status::Custom<Json<APIResult<ExperienceConnectionResponse>>> {
-        match experience_manager.get_experience(id).await {
-            Ok(v) => {
-                if let Err(e) = auth(cookies, config) {
-                    if v.public {
-                        let mut connections = Vec::new();
-                        if let Some(connected_experiences) =
-                            v.events.get(&AvailablePlugins::timeline_plugin_experience)
-                        {
-                            {
-                                for experience in connected_experiences {
-                                    if experience.id != id
-                                        && let Ok(exp) =
-                                            experience_manager.get_experience(&experience.id).await
-                                        && exp.public
-                                    {
-                                        connections.push(ExperienceConnection {
-                                            id: experience.id.clone(),
-                                            name: exp.name,
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                        return status::Custom(
-                            Status::Ok,
-                            Json(Ok(ExperienceConnectionResponse {
-                                public: true,
-                                connections: Vec::new(),
-                                experience_name: v.name,
-                            })),
-                        );
-                    } else {
-                        return status::Custom(Status::Unauthorized, Json(Err(e)));
-                    };
-                }
-
-                *navigator_position.0.write().await = id.to_string();
-                let res = v
-                    .events
-                    .get(&AvailablePlugins::timeline_plugin_experience)
-                    .map(|v| {
-                        v.iter()
-                            .filter_map(|v| {
-                                if v.id != id {
-                                    Some(ExperienceConnection {
-                                        name: v.event.title.clone(),
-                                        id: v.id.clone(),
-                                    })
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect::<Vec<_>>()
-                    })
-                    .unwrap_or(Vec::new());
-                status::Custom(
-                    Status::Ok,
-                    Json(Ok(ExperienceConnectionResponse {
-                        connections: res,
-                        experience_name: v.name.clone(),
-                        public: v.public,
-                    })),
-                )
+        let mut v = match authorize(&caller, experience_manager, id, AccessLevel::Viewer).await {
+            Ok(v) => v,
+            Err((status, e)) => return status::Custom(status, Json(Err(e))),
+        };
+        let user = caller.user.as_ref();
+        let level = access_level(&v, user);
+        let owner = level == AccessLevel::Owner;
+        if !owner {
+            redact_private_events(&mut v);
+        }
+
+        if let Some(user) = user {
+            match positions.set_position(&user.name, id).await {
+                Ok(true) => experience_manager.notify(ExperienceChange::NavigatorPositionChanged {
+                    experience_id: id.to_string(),
+                    user: user.name.clone(),
+                }),
+                Ok(false) => {}
+                Err(e) => eprintln!("Unable to save navigator position: {}", e),
             }
-            Err(e) => match &e {
-                ExperienceError::NotFound(_) => {
-                    status::Custom(Status::NotFound, Json(Err(e.into())))
-                }
-                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
-            },
         }
-    }
 
-    #[post("/navigator/position")]
-    pub async fn get_position(
-        config: &State<Config>,
-        cookies: &CookieJar<'_>,
-        navigator_position: &State<NavigatorPosition>,
-    ) -> status::Custom<Json<APIResult<String>>> {
-        if let Err(e) = auth(cookies, config) {
-            return status::Custom(Status::Unauthorized, Json(Err(e)));
+        let mut res = Vec::new();
+        if let Some(connected_experiences) =
+            v.events.get(&AvailablePlugins::timeline_plugin_experience)
+        {
+            for experience in connected_experiences {
+                if experience.id != id
+                    && let Ok(exp) = experience_manager.get_experience(&experience.id).await
+                    && can_view(&exp, user)
+                {
+                    res.push(ExperienceConnection {
+                        name: exp.name,
+                        id: experience.id.clone(),
+                        kind: ConnectionKind::Peer,
+                        details: v
+                            .connection_details
+                            .get(&experience.id)
+                            .cloned()
+                            .unwrap_or_default(),
+                    });
+                }
+            }
         }
+        res.extend(
+            resolve_connections(
+                experience_manager,
+                v.parent.iter().cloned(),
+                ConnectionKind::Parent,
+                user,
+            )
+            .await,
+        );
+        res.extend(
+            resolve_connections(
+                experience_manager,
+                v.children.iter().cloned(),
+                ConnectionKind::Child,
+                user,
+            )
+            .await,
+        );
         status::Custom(
             Status::Ok,
-            Json(Ok(navigator_position.0.read().await.clone())),
+            Json(Ok(ExperienceConnectionResponse {
+                connections: res,
+                experience_name: v.name.clone(),
+                experience_time: experience_time(&v, id),
+                public: v.public,
+                editable: level >= AccessLevel::Editor && permitted(&caller, TokenScope::Admin),
+                owner,
+            })),
         )
     }
-}
 
-pub fn auth(cookies: &CookieJar<'_>, config: &State<Config>) -> APIResult<()> {
-    match cookies.get("pwd") {
-        Some(pwd) => {
-            if pwd.value() != config.password {
-                Err(APIError::AuthenticationError)
-            } else {
-                Ok(())
-            }
+    #[post("/experience/<id>/connection/<connected_id>", data = "<request>")]
+    pub async fn set_connection_details(
+        id: &str,
+        connected_id: &str,
+        request: Json<ConnectionDetails>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Editor).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, connected_id, AccessLevel::Editor).await
+        {
+            return status::Custom(status, Json(Err(e)));
         }
-        None => Err(APIError::AuthenticationError),
-    }
-}
+
+        match experience_manager
+            .set_connection_details(id, connected_id, request.into_inner())
+            .await
+        {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::Connection, Some(id), Some(connected_id.to_string()))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/experience/<id>/parent", data = "<request>")]
+    pub async fn set_parent(
+        id: &str,
+        request: Json<Option<String>>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Editor).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        if let Some(parent) = request.as_deref()
+            && let Err((status, e)) =
+                authorize(&caller, experience_manager, parent, AccessLevel::Editor).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        let parent = (*request).clone();
+        match experience_manager
+            .set_parent(id, request.into_inner())
+            .await
+        {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::Parent, Some(id), parent)
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                ExperienceError::OperationNowAllowed(_) => {
+                    status::Custom(Status::BadRequest, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/experience/<id>/ancestors")]
+    pub async fn get_ancestors(
+        id: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<Vec<ExperienceConnection>>>> {
+        get_hierarchy(id, &caller, experience_manager, ConnectionKind::Parent).await
+    }
+
+    #[post("/experience/<id>/descendants")]
+    pub async fn get_descendants(
+        id: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<Vec<ExperienceConnection>>>> {
+        get_hierarchy(id, &caller, experience_manager, ConnectionKind::Child).await
+    }
+
+    async fn get_hierarchy(
+        id: &str,
+        caller: &Caller,
+        experience_manager: &State<ExperienceManager>,
+        kind: ConnectionKind,
+    ) -> status::Custom<Json<APIResult<Vec<ExperienceConnection>>>> {
+        if let Err((status, e)) =
+            authorize(caller, experience_manager, id, AccessLevel::Viewer).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        let ids = match kind {
+            ConnectionKind::Child => experience_manager.get_descendants(id).await,
+            _ => experience_manager.get_ancestors(id).await,
+        };
+        match ids {
+            Ok(v) => status::Custom(
+                Status::Ok,
+                Json(Ok(resolve_connections(
+                    experience_manager,
+                    v.into_iter(),
+                    kind,
+                    caller.user.as_ref(),
+                )
+                .await)),
+            ),
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+
+    async fn resolve_connections(
+        experience_manager: &ExperienceManager,
+        ids: impl Iterator<Item = String>,
+        kind: ConnectionKind,
+        user: Option<&User>,
+    ) -> Vec<ExperienceConnection> {
+        let mut connections = Vec::new();
+        for id in ids {
+            if let Ok(experience) = experience_manager.get_experience(&id).await
+                && can_view(&experience, user)
+            {
+                connections.push(ExperienceConnection {
+                    id,
+                    name: experience.name,
+                    kind: kind.clone(),
+                    details: ConnectionDetails::default(),
+                });
+            }
+        }
+        connections
+    }
+
+    #[post("/navigator/position")]
+    pub async fn get_position(
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+        positions: &State<PositionManager>,
+    ) -> status::Custom<Json<APIResult<String>>> {
+        let user = match auth(&caller) {
+            Ok(v) => v,
+            Err(e) => return status::Custom(Status::Unauthorized, Json(Err(e))),
+        };
+
+        if let Some(position) = positions.get_position(&user.name).await
+            && let Ok(experience) = experience_manager.get_experience(&position).await
+            && can_view(&experience, Some(user))
+        {
+            return status::Custom(Status::Ok, Json(Ok(position)));
+        }
+
+        match experience_manager
+            .most_recent_experience(|v| can_view(v, Some(user)))
+            .await
+        {
+            Ok(Some(v)) => status::Custom(Status::Ok, Json(Ok(v))),
+            Ok(None) => status::Custom(
+                Status::NotFound,
+                Json(Err(ExperienceError::NotFound("navigator position".to_string()).into())),
+            ),
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+}
+
+pub mod graph {
+    use {
+        super::*,
+        crate::experience_manager::ExperienceManager,
+        server_api::{
+            graph::ExperienceGraph,
+            graph_export::{export_graph, GraphFormat},
+        },
+        shared::types::{ExperienceSubgraph, GraphNode},
+    };
+
+    #[get("/graph/export/<format>")]
+    pub async fn export(
+        format: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Option<(ContentType, String)>> {
+        let format = match format.parse::<GraphFormat>() {
+            Ok(v) => v,
+            Err(_) => return status::Custom(Status::NotFound, None),
+        };
+        let graph = match ExperienceGraph::load(experience_manager, |v| {
+            can_view(v, caller.user.as_ref())
+        })
+        .await
+        {
+            Ok(v) => v,
+            Err(_) => return status::Custom(Status::InternalServerError, None),
+        };
+
+        match export_graph(&graph, format) {
+            Ok(v) => status::Custom(
+                Status::Ok,
+                Some((
+                    ContentType::parse_flexible(format.content_type()).unwrap_or(ContentType::Text),
+                    v,
+                )),
+            ),
+            Err(_) => status::Custom(Status::InternalServerError, None),
+        }
+    }
+
+    #[post("/graph/<id>/neighborhood/<depth>")]
+    pub async fn neighborhood(
+        id: &str,
+        depth: usize,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<ExperienceSubgraph>>> {
+        let graph = match ExperienceGraph::load(experience_manager, |v| {
+            can_view(v, caller.user.as_ref())
+        })
+        .await
+        {
+            Ok(v) => v,
+            Err(e) => return status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        };
+
+        match graph.neighborhood(id, depth) {
+            Some(v) => status::Custom(Status::Ok, Json(Ok(v))),
+            None => status::Custom(
+                Status::NotFound,
+                Json(Err(ExperienceError::NotFound(id.to_string()).into())),
+            ),
+        }
+    }
+
+    #[post("/graph/path/<from>/<to>")]
+    pub async fn shortest_path(
+        from: &str,
+        to: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<Option<Vec<GraphNode>>>>> {
+        let graph = match ExperienceGraph::load(experience_manager, |v| {
+            can_view(v, caller.user.as_ref())
+        })
+        .await
+        {
+            Ok(v) => v,
+            Err(e) => return status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        };
+
+        for id in [from, to] {
+            if !graph.contains(id) {
+                return status::Custom(
+                    Status::NotFound,
+                    Json(Err(ExperienceError::NotFound(id.to_string()).into())),
+                );
+            }
+        }
+
+        status::Custom(Status::Ok, Json(Ok(graph.shortest_path(from, to))))
+    }
+
+    #[post("/graph/components")]
+    pub async fn components(
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<Vec<Vec<GraphNode>>>>> {
+        match ExperienceGraph::load(experience_manager, |v| can_view(v, caller.user.as_ref()))
+            .await
+        {
+            Ok(v) => status::Custom(Status::Ok, Json(Ok(v.components()))),
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+
+    #[post("/graph/isolated")]
+    pub async fn isolated(
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<Vec<GraphNode>>>> {
+        match ExperienceGraph::load(experience_manager, |v| can_view(v, caller.user.as_ref()))
+            .await
+        {
+            Ok(v) => status::Custom(Status::Ok, Json(Ok(v.isolated()))),
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+}
+
+pub mod templates {
+    use {
+        super::*, crate::experience_manager::ExperienceManager, std::collections::HashMap,
+    };
+
+    #[post("/templates")]
+    pub async fn get_templates(
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<HashMap<String, ExperienceTemplate>>>> {
+        let user = match auth(&caller) {
+            Ok(v) => v,
+            Err(e) => return status::Custom(Status::Unauthorized, Json(Err(e))),
+        };
+
+        match experience_manager.get_templates().await {
+            Ok(v) => status::Custom(
+                Status::Ok,
+                Json(Ok(v
+                    .into_iter()
+                    .filter(|(_, template)| owns(&template.owner, user))
+                    .collect())),
+            ),
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+
+    #[post("/templates/create", data = "<request>")]
+    pub async fn save_template(
+        request: Json<SaveTemplateRequest>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<String>>> {
+        if let Err((status, e)) = authorize(
+            &caller,
+            experience_manager,
+            &request.experience_id,
+            AccessLevel::Owner,
+        )
+        .await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        let request = request.into_inner();
+        let details = request.name.clone();
+        match experience_manager
+            .save_template(
+                &request.experience_id,
+                request.name,
+                request.name_pattern,
+                caller.user.as_ref().map(|v| v.name.clone()),
+            )
+            .await
+        {
+            Ok(v) => {
+                caller
+                    .audit(
+                        AuditAction::Template,
+                        Some(&request.experience_id),
+                        Some(format!("Saved template {}", details)),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(v)))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/templates/<id>/delete")]
+    pub async fn delete_template(
+        id: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        let user = match auth_scoped(&caller, TokenScope::Admin) {
+            Ok(v) => v,
+            Err((status, e)) => return status::Custom(status, Json(Err(e))),
+        };
+
+        match experience_manager.get_template(id).await {
+            Ok(v) if owns(&v.owner, user) => {}
+            Ok(_) => {
+                return status::Custom(
+                    Status::NotFound,
+                    Json(Err(ExperienceError::NotFound(id.to_string()).into())),
+                )
+            }
+            Err(e) => {
+                return match &e {
+                    ExperienceError::NotFound(_) => {
+                        status::Custom(Status::NotFound, Json(Err(e.into())))
+                    }
+                    _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+                }
+            }
+        }
+
+        match experience_manager.delete_template(id).await {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::Template, None, Some(format!("Deleted template {}", id)))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+}
+
+pub mod backup {
+    use {super::*, crate::experience_manager::ExperienceManager};
+
+    #[post("/backup")]
+    pub async fn create_backup(
+        config: &State<Config>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<String>>> {
+        if let Err((status, e)) = admin(&caller) {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match experience_manager.backup(&config.backups_folder).await {
+            Ok(v) => {
+                let name = v
+                    .file_name()
+                    .map(|v| v.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                caller
+                    .audit(AuditAction::Backup, None, Some(name.clone()))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(name)))
+            }
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+}
+
+pub mod memories {
+    use {
+        super::*,
+        crate::experience_manager::ExperienceManager,
+        chrono::{NaiveDate, Utc},
+        server_api::memories::on_this_day,
+        shared::types::Memories,
+    };
+
+    #[post("/memories")]
+    pub async fn today(
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<Memories>>> {
+        get_memories(Utc::now().date_naive(), &caller, experience_manager).await
+    }
+
+    #[post("/memories/<date>")]
+    pub async fn on_date(
+        date: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<Memories>>> {
+        let date = match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(v) => v,
+            Err(e) => {
+                return status::Custom(
+                    Status::BadRequest,
+                    Json(Err(ExperienceError::ParsingError(e.to_string()).into())),
+                )
+            }
+        };
+
+        get_memories(date, &caller, experience_manager).await
+    }
+
+    async fn get_memories(
+        date: NaiveDate,
+        caller: &Caller,
+        experience_manager: &ExperienceManager,
+    ) -> status::Custom<Json<APIResult<Memories>>> {
+        match on_this_day(experience_manager, date, |v| {
+            access_level(v, caller.user.as_ref())
+        })
+        .await
+        {
+            Ok(v) => status::Custom(Status::Ok, Json(Ok(v))),
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+}
+
+pub mod suggestions {
+    use {
+        super::*,
+        crate::experience_manager::ExperienceManager,
+        server_api::{suggestions::suggest, timeline::TimelineClient},
+        shared::types::{AcceptSuggestionRequest, ExperienceSuggestion, SuggestionsRequest},
+    };
+
+    #[post("/suggestions", data = "<request>")]
+    pub async fn get_suggestions(
+        request: Json<SuggestionsRequest>,
+        caller: Caller,
+        timeline: &State<TimelineClient>,
+    ) -> status::Custom<Json<APIResult<Vec<ExperienceSuggestion>>>> {
+        if let Err(e) = auth(&caller) {
+            return status::Custom(Status::Unauthorized, Json(Err(e)));
+        }
+
+        match suggest(timeline, &request).await {
+            Ok(v) => status::Custom(Status::Ok, Json(Ok(v))),
+            Err(e) => match &e {
+                ExperienceError::TimelineError(_) => {
+                    status::Custom(Status::BadGateway, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/suggestions/accept", data = "<request>")]
+    pub async fn accept_suggestion(
+        request: Json<AcceptSuggestionRequest>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<String>>> {
+        let user = match auth_scoped(&caller, TokenScope::AppendOnly) {
+            Ok(v) => v,
+            Err((status, e)) => return status::Custom(status, Json(Err(e))),
+        };
+
+        let request = request.into_inner();
+        let id = match experience_manager
+            .create_experience(request.name, request.time, None, Some(user.name.clone()))
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => return status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        };
+
+        match experience_manager
+            .append_events(
+                &id,
+                request
+                    .events
+                    .into_iter()
+                    .map(|v| (v.plugin, v.event))
+                    .collect(),
+            )
+            .await
+        {
+            Ok(v) => {
+                caller
+                    .audit(
+                        AuditAction::Create,
+                        Some(&id),
+                        Some(format!("Accepted suggestion with {} events", v.len())),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(id)))
+            }
+            Err(e) => {
+                if let Err(e) = experience_manager.discard_experience(&id).await {
+                    eprintln!("Unable to discard experience {}: {}", id, e);
+                }
+                match &e {
+                    ExperienceError::OperationNowAllowed(_) => {
+                        status::Custom(Status::BadRequest, Json(Err(e.into())))
+                    }
+                    _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+                }
+            }
+        }
+    }
+}
+
+pub mod live {
+    use {
+        super::*,
+        crate::experience_manager::ExperienceManager,
+        rocket::{
+            response::stream::{Event, EventStream},
+            tokio::select,
+            Shutdown,
+        },
+        tokio::sync::broadcast::error::RecvError,
+    };
+
+    #[get("/events")]
+    pub fn events(
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+        mut shutdown: Shutdown,
+    ) -> EventStream![] {
+        let user = caller.user;
+        let mut changes = experience_manager.subscribe();
+        let experience_manager = experience_manager.inner().clone();
+
+        EventStream! {
+            loop {
+                let change = select! {
+                    change = changes.recv() => match change {
+                        Ok(v) => v,
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                    _ = &mut shutdown => break,
+                };
+                if !visible(&experience_manager, user.as_ref(), &change).await {
+                    continue;
+                }
+                yield Event::json(&change).event(change.event_name());
+            }
+        }
+    }
+
+    async fn visible(
+        experience_manager: &ExperienceManager,
+        user: Option<&User>,
+        change: &ExperienceChange,
+    ) -> bool {
+        match change {
+            ExperienceChange::NavigatorPositionChanged { user: owner, .. } => {
+                return user.is_some_and(|v| v.name == *owner)
+            }
+            ExperienceChange::Deleted { .. } => return user.is_some_and(|v| v.admin),
+            _ => {}
+        }
+        let connected_id = match change {
+            ExperienceChange::Connected { connected_id, .. }
+            | ExperienceChange::Disconnected { connected_id, .. } => Some(connected_id),
+            _ => None,
+        };
+        for id in [Some(change.experience_id()), connected_id.map(|v| v.as_str())]
+            .into_iter()
+            .flatten()
+        {
+            if !experience_manager
+                .get_experience(id)
+                .await
+                .is_ok_and(|v| can_view(&v, user))
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub mod shares {
+    use {
+        super::*,
+        rocket::response::Redirect,
+        shared::types::{CreateShareRequest, ShareToken},
+    };
+
+    #[post("/experience/<id>/shares")]
+    pub async fn get_shares(
+        id: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+        shares: &State<ShareManager>,
+    ) -> status::Custom<Json<APIResult<Vec<ShareToken>>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Owner).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        status::Custom(Status::Ok, Json(Ok(shares.get_shares(id).await)))
+    }
+
+    #[post("/experience/<id>/shares/create", data = "<request>")]
+    pub async fn create_share(
+        id: &str,
+        request: Json<CreateShareRequest>,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+        shares: &State<ShareManager>,
+    ) -> status::Custom<Json<APIResult<ShareToken>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Owner).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match shares
+            .create_share(id, request.expires_in_hours, request.max_views)
+            .await
+        {
+            Ok(v) => {
+                caller
+                    .audit(
+                        AuditAction::Share,
+                        Some(id),
+                        Some(format!("Created share link {}", v.created)),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(v)))
+            }
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+
+    #[post("/experience/<id>/shares/<token>/revoke")]
+    pub async fn revoke_share(
+        id: &str,
+        token: &str,
+        caller: Caller,
+        experience_manager: &State<ExperienceManager>,
+        shares: &State<ShareManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        if let Err((status, e)) =
+            authorize(&caller, experience_manager, id, AccessLevel::Owner).await
+        {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match shares.revoke_share(id, token).await {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::Share, Some(id), Some("Revoked share link".to_string()))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[get("/share/<token>")]
+    pub async fn open_share(
+        token: &str,
+        caller: Caller,
+        cookies: &CookieJar<'_>,
+        shares: &State<ShareManager>,
+        config: &State<Config>,
+    ) -> Result<Redirect, Status> {
+        if caller.rate_limited() {
+            return Err(Status::TooManyRequests);
+        }
+        match shares.redeem(token).await {
+            Ok((id, expires)) => {
+                let now = Utc::now();
+                let until = (now + Duration::hours(SHARE_ACCESS_HOURS))
+                    .min(expires.unwrap_or(DateTime::<Utc>::MAX_UTC));
+                cookies.add_private(
+                    Cookie::build((
+                        format!("{}{}", SHARE_COOKIE_PREFIX, id),
+                        format!("{}:{}", until.timestamp(), token),
+                    ))
+                    .path("/")
+                    .http_only(true)
+                    .same_site(SameSite::Lax)
+                    .secure(config.secure_cookies)
+                    .max_age(rocket::time::Duration::seconds((until - now).num_seconds())),
+                );
+                Ok(Redirect::to(format!("/experience/{}", id)))
+            }
+            Err(ExperienceError::NotFound(_)) => {
+                caller.record_failure();
+                caller
+                    .audit(AuditAction::AuthFailure, None, Some("Invalid share link".to_string()))
+                    .await;
+                Err(Status::NotFound)
+            }
+            Err(_) => Err(Status::InternalServerError),
+        }
+    }
+}
+
+pub mod users {
+    use {
+        super::*,
+        shared::types::{CreateUserRequest, UserInfo},
+    };
+
+    #[post("/user")]
+    pub async fn get_user(caller: Caller) -> status::Custom<Json<APIResult<UserInfo>>> {
+        match auth(&caller) {
+            Ok(v) => status::Custom(Status::Ok, Json(Ok(v.info()))),
+            Err(e) => status::Custom(Status::Unauthorized, Json(Err(e))),
+        }
+    }
+
+    #[post("/users")]
+    pub async fn get_users(
+        caller: Caller,
+        users: &State<UserManager>,
+    ) -> status::Custom<Json<APIResult<Vec<UserInfo>>>> {
+        if let Err((status, e)) = admin(&caller) {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        status::Custom(Status::Ok, Json(Ok(users.get_users().await)))
+    }
+
+    #[post("/users/create", data = "<request>")]
+    pub async fn create_user(
+        request: Json<CreateUserRequest>,
+        caller: Caller,
+        users: &State<UserManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        if let Err((status, e)) = admin(&caller) {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match users
+            .create_user(
+                &request.name,
+                &request.password,
+                request.admin,
+                request.groups.clone(),
+            )
+            .await
+        {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::User, None, Some(format!("Created user {}", request.name)))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::OperationNowAllowed(_) => {
+                    status::Custom(Status::BadRequest, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+
+    #[post("/users/<name>/delete")]
+    pub async fn delete_user(
+        name: &str,
+        caller: Caller,
+        users: &State<UserManager>,
+        tokens: &State<TokenManager>,
+        positions: &State<PositionManager>,
+        experience_manager: &State<ExperienceManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        if let Err((status, e)) = admin(&caller) {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        if let Err(e) = users.delete_user(name).await {
+            return match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            };
+        }
+
+        if let Err(e) = tokens.revoke_user_tokens(name).await {
+            return status::Custom(Status::InternalServerError, Json(Err(e.into())));
+        }
+
+        if let Err(e) = positions.remove_position(name).await {
+            return status::Custom(Status::InternalServerError, Json(Err(e.into())));
+        }
+
+        match experience_manager.release_experiences(name).await {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::User, None, Some(format!("Deleted user {}", name)))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+}
+
+pub mod tokens {
+    use {
+        super::*,
+        shared::types::{ApiTokenInfo, CreateApiTokenRequest, CreatedApiToken},
+    };
+
+    #[post("/tokens")]
+    pub async fn get_tokens(
+        caller: Caller,
+        tokens: &State<TokenManager>,
+    ) -> status::Custom<Json<APIResult<Vec<ApiTokenInfo>>>> {
+        match auth(&caller) {
+            Ok(user) => {
+                status::Custom(Status::Ok, Json(Ok(tokens.get_tokens(&user.name).await)))
+            }
+            Err(e) => status::Custom(Status::Unauthorized, Json(Err(e))),
+        }
+    }
+
+    #[post("/tokens/create", data = "<request>")]
+    pub async fn create_token(
+        request: Json<CreateApiTokenRequest>,
+        caller: Caller,
+        tokens: &State<TokenManager>,
+    ) -> status::Custom<Json<APIResult<CreatedApiToken>>> {
+        let user = match auth_scoped(&caller, TokenScope::Admin) {
+            Ok(v) => v,
+            Err((status, e)) => return status::Custom(status, Json(Err(e))),
+        };
+
+        let request = request.into_inner();
+        match tokens
+            .create_token(&user.name, request.name, request.scope)
+            .await
+        {
+            Ok(v) => {
+                caller
+                    .audit(
+                        AuditAction::Token,
+                        None,
+                        Some(format!("Created {:?} token {}", v.info.scope, v.info.name)),
+                    )
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(v)))
+            }
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+
+    #[post("/tokens/<id>/revoke")]
+    pub async fn revoke_token(
+        id: &str,
+        caller: Caller,
+        tokens: &State<TokenManager>,
+    ) -> status::Custom<Json<APIResult<()>>> {
+        let user = match auth_scoped(&caller, TokenScope::Admin) {
+            Ok(v) => v,
+            Err((status, e)) => return status::Custom(status, Json(Err(e))),
+        };
+
+        match tokens.revoke_token(&user.name, id).await {
+            Ok(_) => {
+                caller
+                    .audit(AuditAction::Token, None, Some(format!("Revoked token {}", id)))
+                    .await;
+                status::Custom(Status::Ok, Json(Ok(())))
+            }
+            Err(e) => match &e {
+                ExperienceError::NotFound(_) => {
+                    status::Custom(Status::NotFound, Json(Err(e.into())))
+                }
+                _ => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+            },
+        }
+    }
+}
+
+pub mod audit {
+    use {super::*, shared::types::{AuditEntry, AuditQuery}};
+
+    #[post("/audit", data = "<request>")]
+    pub async fn query(
+        request: Json<AuditQuery>,
+        caller: Caller,
+        audit: &State<Arc<AuditLog>>,
+    ) -> status::Custom<Json<APIResult<Vec<AuditEntry>>>> {
+        if let Err((status, e)) = admin(&caller) {
+            return status::Custom(status, Json(Err(e)));
+        }
+
+        match audit.query(&request).await {
+            Ok(v) => status::Custom(Status::Ok, Json(Ok(v))),
+            Err(e) => status::Custom(Status::InternalServerError, Json(Err(e.into()))),
+        }
+    }
+}
+
+pub struct Caller {
+    pub user: Option<User>,
+    pub scope: Option<TokenScope>,
+    pub shares: HashSet<String>,
+    pub ip: Option<IpAddr>,
+    audit: Option<Arc<AuditLog>>,
+    limiter: Option<Arc<RateLimiter>>,
+}
+
+impl Caller {
+    pub fn rate_limited(&self) -> bool {
+        self.limiter.as_ref().is_some_and(|v| v.check(self.ip).is_err())
+    }
+
+    pub fn record_failure(&self) {
+        if let Some(limiter) = &self.limiter {
+            limiter.record_failure(self.ip);
+        }
+    }
+
+    pub async fn audit(
+        &self,
+        action: AuditAction,
+        experience_id: Option<&str>,
+        details: Option<String>,
+    ) {
+        if let Some(audit) = &self.audit {
+            audit
+                .record(&entry(
+                    action,
+                    self.user.as_ref().map(|v| v.name.clone()),
+                    self.ip,
+                    experience_id,
+                    details,
+                ))
+                .await;
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Caller {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let ip = request.client_ip();
+        let cookies = request.cookies();
+        let users = request.guard::<&State<UserManager>>().await.succeeded();
+        let limiter = request
+            .guard::<&State<Arc<RateLimiter>>>()
+            .await
+            .succeeded()
+            .map(|v| v.inner().clone());
+        let audit = request
+            .guard::<&State<Arc<AuditLog>>>()
+            .await
+            .succeeded()
+            .map(|v| v.inner().clone());
+        let bearer = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|v| v.strip_prefix("Bearer "));
+
+        let (user, scope) = match (users, bearer) {
+            (Some(users), Some(token)) => {
+                if limiter.as_ref().is_some_and(|v| v.check(ip).is_err()) {
+                    return Outcome::Error((Status::TooManyRequests, ()));
+                }
+                let authenticated = match request.guard::<&State<TokenManager>>().await {
+                    Outcome::Success(tokens) => tokens.authenticate(token).await,
+                    _ => None,
+                };
+                match authenticated {
+                    Some((name, scope)) => {
+                        if let Some(limiter) = &limiter {
+                            limiter.record_success(ip);
+                        }
+                        (users.get_user(&name).await, Some(scope))
+                    }
+                    None => {
+                        if let Some(limiter) = &limiter {
+                            limiter.record_failure(ip);
+                        }
+                        if let Some(audit) = &audit {
+                            audit
+                                .record(&entry(
+                                    AuditAction::AuthFailure,
+                                    None,
+                                    ip,
+                                    None,
+                                    Some("Invalid API token".to_string()),
+                                ))
+                                .await;
+                        }
+                        (None, None)
+                    }
+                }
+            }
+            (Some(users), None) => match cookies.get_private(SESSION_COOKIE) {
+                Some(session) => match unexpired(session.value())
+                    .and_then(|v| v.split_once(':'))
+                {
+                    Some((key, name)) => (
+                        users
+                            .get_user(name)
+                            .await
+                            .filter(|v| v.session_key() == key),
+                        None,
+                    ),
+                    None => (None, None),
+                },
+                None => (None, None),
+            },
+            _ => (None, None),
+        };
+        let mut shares = HashSet::new();
+        if let Outcome::Success(share_manager) = request.guard::<&State<ShareManager>>().await {
+            let names = cookies
+                .iter()
+                .filter(|v| v.name().starts_with(SHARE_COOKIE_PREFIX))
+                .map(|v| v.name().to_string())
+                .collect::<Vec<_>>();
+            for name in names {
+                let id = &name[SHARE_COOKIE_PREFIX.len()..];
+                if let Some(cookie) = cookies.get_private(&name)
+                    && let Some(token) = unexpired(cookie.value())
+                    && share_manager.shared_experience(token).await.as_deref() == Some(id)
+                {
+                    shares.insert(id.to_string());
+                }
+            }
+        }
+        Outcome::Success(Caller {
+            user,
+            scope,
+            shares,
+            ip,
+            audit,
+            limiter,
+        })
+    }
+}
+
+const SESSION_COOKIE: &str = "session";
+const SHARE_COOKIE_PREFIX: &str = "share-";
+const SHARE_ACCESS_HOURS: i64 = 24;
+
+fn unexpired(value: &str) -> Option<&str> {
+    let (expires, value) = value.split_once(':')?;
+    if expires.parse::<i64>().ok()? > Utc::now().timestamp() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+pub fn auth(caller: &Caller) -> APIResult<&User> {
+    caller.user.as_ref().ok_or(APIError::AuthenticationError)
+}
+
+fn permitted(caller: &Caller, scope: TokenScope) -> bool {
+    caller.scope.is_none_or(|v| v >= scope)
+}
+
+pub fn auth_scoped(caller: &Caller, scope: TokenScope) -> Result<&User, (Status, APIError)> {
+    match &caller.user {
+        Some(_) if !permitted(caller, scope) => {
+            Err((Status::Forbidden, APIError::AuthenticationError))
+        }
+        Some(user) => Ok(user),
+        None => Err((Status::Unauthorized, APIError::AuthenticationError)),
+    }
+}
+
+pub fn admin(caller: &Caller) -> Result<&User, (Status, APIError)> {
+    match &caller.user {
+        Some(user) if user.admin && permitted(caller, TokenScope::Admin) => Ok(user),
+        Some(_) => Err((Status::Forbidden, APIError::AuthenticationError)),
+        None => Err((Status::Unauthorized, APIError::AuthenticationError)),
+    }
+}
+
+pub async fn authorize(
+    caller: &Caller,
+    experience_manager: &ExperienceManager,
+    id: &str,
+    required: AccessLevel,
+) -> Result<Experience, (Status, APIError)> {
+    let scope = match required {
+        AccessLevel::None | AccessLevel::Viewer => TokenScope::ReadOnly,
+        AccessLevel::Editor | AccessLevel::Owner => TokenScope::Admin,
+    };
+    authorize_scoped(caller, experience_manager, id, required, scope).await
+}
+
+async fn redact_hidden_connections(
+    caller: &Caller,
+    experience_manager: &ExperienceManager,
+    id: &str,
+    experience: &mut Experience,
+) {
+    let mut connected = experience
+        .events
+        .get(&AvailablePlugins::timeline_plugin_experience)
+        .map(|v| v.iter().map(|v| v.id.clone()).collect::<HashSet<_>>())
+        .unwrap_or_default();
+    connected.extend(experience.parent.iter().cloned());
+    connected.extend(experience.children.iter().cloned());
+    connected.extend(experience.connection_details.keys().cloned());
+    connected.remove(id);
+
+    let mut hidden = HashSet::new();
+    for connected_id in connected {
+        let visible = caller.shares.contains(&connected_id)
+            || experience_manager
+                .get_experience(&connected_id)
+                .await
+                .is_ok_and(|v| can_view(&v, caller.user.as_ref()));
+        if !visible {
+            hidden.insert(connected_id);
+        }
+    }
+    if hidden.is_empty() {
+        return;
+    }
+
+    if let Some(events) = experience
+        .events
+        .get_mut(&AvailablePlugins::timeline_plugin_experience)
+    {
+        events.retain(|v| !hidden.contains(&v.id));
+    }
+    experience.parent = experience.parent.take().filter(|v| !hidden.contains(v));
+    experience.children.retain(|v| !hidden.contains(v));
+    experience.connection_details.retain(|v, _| !hidden.contains(v));
+}
+
+pub async fn authorize_scoped(
+    caller: &Caller,
+    experience_manager: &ExperienceManager,
+    id: &str,
+    required: AccessLevel,
+    scope: TokenScope,
+) -> Result<Experience, (Status, APIError)> {
+    if caller.rate_limited() {
+        return Err((Status::TooManyRequests, APIError::AuthenticationError));
+    }
+    let experience = match experience_manager.get_experience(id).await {
+        Ok(v) => v,
+        Err(e) => {
+            return Err(match &e {
+                ExperienceError::NotFound(_) => (Status::NotFound, e.into()),
+                _ => (Status::InternalServerError, e.into()),
+            })
+        }
+    };
+
+    let mut level = access_level(&experience, caller.user.as_ref());
+    if caller.shares.contains(id) {
+        level = level.max(AccessLevel::Viewer);
+    }
+
+    if level >= required && permitted(caller, scope) {
+        return Ok(experience);
+    }
+
+    caller.record_failure();
+    caller
+        .audit(
+            AuditAction::AuthFailure,
+            Some(id),
+            Some(format!("Requires {:?} with {:?} scope", required, scope)),
+        )
+        .await;
+    if caller.user.is_none() {
+        Err((Status::Unauthorized, APIError::AuthenticationError))
+    } else {
+        Err((Status::Forbidden, APIError::AuthenticationError))
+    }
+}
 
 #[post("/timeline_url")]
 pub fn timeline_url(config: &State<Config>) -> status::Accepted<Json<APIResult<String>>> {
     status::Accepted(Json(Ok(config.timeline_url.to_string())))
 }
 
-#[post("/auth")]
-pub fn auth_request(
+#[post("/login", data = "<request>")]
+pub async fn login(
+    request: Json<LoginRequest>,
+    ip: Option<IpAddr>,
     config: &State<Config>,
     cookies: &CookieJar<'_>,
+    users: &State<UserManager>,
+    limiter: &State<Arc<RateLimiter>>,
+    audit: &State<Arc<AuditLog>>,
+) -> status::Custom<Json<APIResult<UserInfo>>> {
+    if limiter.check(ip).is_err() {
+        return status::Custom(
+            Status::TooManyRequests,
+            Json(Err(APIError::AuthenticationError)),
+        );
+    }
+
+    let user = match users.authenticate(&request.name, &request.password).await {
+        Some(v) => v,
+        None => {
+            limiter.record_failure(ip);
+            audit
+                .record(&entry(
+                    AuditAction::AuthFailure,
+                    Some(request.name.clone()),
+                    ip,
+                    None,
+                    Some("Invalid login".to_string()),
+                ))
+                .await;
+            return status::Custom(Status::Unauthorized, Json(Err(APIError::AuthenticationError)));
+        }
+    };
+    limiter.record_success(ip);
+    audit
+        .record(&entry(
+            AuditAction::Login,
+            Some(user.name.clone()),
+            ip,
+            None,
+            None,
+        ))
+        .await;
+
+    let lifetime = Duration::hours(config.session_hours as i64);
+    cookies.add_private(
+        Cookie::build((
+            SESSION_COOKIE,
+            format!(
+                "{}:{}:{}",
+                (Utc::now() + lifetime).timestamp(),
+                user.session_key(),
+                user.name
+            ),
+        ))
+        .path("/")
+        .http_only(true)
+        .secure(config.secure_cookies)
+        .max_age(rocket::time::Duration::hours(config.session_hours as i64)),
+    );
+    status::Custom(Status::Ok, Json(Ok(user.info())))
+}
+
+#[post("/logout")]
+pub async fn logout(
+    caller: Caller,
+    cookies: &CookieJar<'_>,
+    users: &State<UserManager>,
 ) -> status::Custom<Json<APIResult<()>>> {
-    status::Custom(Status::Ok, Json(auth(cookies, config)))
+    if let Some(user) = &caller.user {
+        if caller.scope.is_none()
+            && let Err(e) = users.revoke_sessions(&user.name).await
+        {
+            return status::Custom(Status::InternalServerError, Json(Err(e.into())));
+        }
+        caller.audit(AuditAction::Logout, None, None).await;
+    }
+    cookies.remove_private(Cookie::build(SESSION_COOKIE).path("/"));
+    status::Custom(Status::Ok, Json(Ok(())))
+}
+
+#[post("/auth")]
+pub fn auth_request(caller: Caller) -> status::Custom<Json<APIResult<()>>> {
+    status::Custom(Status::Ok, Json(auth(&caller).map(|_| ())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::renderer::Renderer,
+        rocket::{local::asynchronous::Client, routes, Build, Rocket},
+        server_api::plugin::PluginRenderer,
+        shared::types::{AccessRole, AccessSubject, CompressedExperienceEvent},
+        std::{collections::HashMap, future::Future, net::SocketAddr, path::PathBuf, pin::Pin},
+        timeline_types::timing::Timing,
+    };
+
+    struct TestServer {
+        folder: PathBuf,
+        config: Config,
+        experience_manager: ExperienceManager,
+    }
+
+    impl TestServer {
+        async fn new() -> TestServer {
+            TestServer::with_renderers(HashMap::new()).await
+        }
+
+        async fn with_renderers(
+            renderers: HashMap<AvailablePlugins, Box<dyn PluginRenderer>>,
+        ) -> TestServer {
+            let folder = std::env::temp_dir().join(format!("server-{}", uuid::Uuid::new_v4()));
+            let path = |v: &str| folder.join(v).display().to_string();
+            std::fs::create_dir_all(path("experiences")).unwrap();
+            std::fs::create_dir_all(path("covers")).unwrap();
+            let config: Config = toml::from_str(&format!(
+                "password = \"admin\"\nport = 0\nexperiences_folder = {:?}\n\
+                 covers_folder = {:?}\ntimeline_url = \"http://127.0.0.1:1/\"\n\
+                 users_file = {:?}\nshares_file = {:?}\naudit_log = {:?}\n\
+                 secure_cookies = false\n[rate_limit]\nip_failures = 2\n",
+                path("experiences"),
+                path("covers"),
+                path("users.json"),
+                path("shares.json"),
+                path("audit.log"),
+            ))
+            .unwrap();
+            let experience_manager =
+                ExperienceManager::new(&config, Arc::new(Renderer::new(renderers))).await;
+            TestServer {
+                folder,
+                config,
+                experience_manager,
+            }
+        }
+
+        async fn create_experience(&self, name: &str, public: bool) -> String {
+            let time = Timing::Instant(Utc::now());
+            let id = self
+                .experience_manager
+                .create_experience(name.to_string(), time, None, Some("alice".to_string()))
+                .await
+                .unwrap();
+            self.experience_manager
+                .set_experience_visibility(&id, public)
+                .await
+                .unwrap();
+            id
+        }
+
+        async fn client(&self, rocket: Rocket<Build>) -> Client {
+            let users = UserManager::load(&self.config).await.unwrap();
+            let _ = users.create_user("viewer", "viewer", false, Vec::new()).await;
+            Client::tracked(
+                rocket
+                    .manage(users)
+                    .manage(ShareManager::load(&self.config).await.unwrap())
+                    .manage(Arc::new(RateLimiter::new(self.config.rate_limit.clone())))
+                    .manage(Arc::new(AuditLog::new(&self.config)))
+                    .manage(self.config.clone())
+                    .manage(self.experience_manager.clone()),
+            )
+            .await
+            .unwrap()
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.folder);
+        }
+    }
+
+    fn remote() -> SocketAddr {
+        ([10, 0, 0, 1], 1234).into()
+    }
+
+    #[rocket::async_test]
+    async fn share_lookups_are_rate_limited() {
+        let server = TestServer::new().await;
+        let client = server
+            .client(rocket::build().mount("/api", routes![shares::open_share]))
+            .await;
+        for status in [Status::NotFound, Status::NotFound, Status::TooManyRequests] {
+            let response = client.get("/api/share/guess").remote(remote()).dispatch().await;
+            assert_eq!(response.status(), status);
+        }
+    }
+
+    #[rocket::async_test]
+    async fn authorization_failures_are_rate_limited() {
+        let server = TestServer::new().await;
+        let private = server.create_experience("Private", false).await;
+        let public = server.create_experience("Public", true).await;
+        let client = server
+            .client(rocket::build().mount("/api", routes![experiences::get_experience]))
+            .await;
+        let get = |id: &str| {
+            client
+                .post(format!("/api/experience/{}", id))
+                .remote(remote())
+                .dispatch()
+        };
+
+        assert_eq!(get(&public).await.status(), Status::Accepted);
+        assert_eq!(get(&private).await.status(), Status::Unauthorized);
+        assert_eq!(get(&private).await.status(), Status::Unauthorized);
+        assert_eq!(get(&public).await.status(), Status::TooManyRequests);
+    }
+
+    const HIDDEN_PIXEL: u32 = 0xffffffff;
+
+    struct HiddenRenderer;
+
+    impl PluginRenderer for HiddenRenderer {
+        async fn new() -> Self {
+            HiddenRenderer
+        }
+
+        fn render(
+            &self,
+            dimensions: (i32, i32),
+            event: &CompressedEvent,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u32>, String>> + Send>> {
+            let pixel = match event.title == "Hidden" {
+                true => HIDDEN_PIXEL,
+                false => 0xff000080,
+            };
+            let len = (dimensions.0 * dimensions.1) as usize;
+            Box::pin(async move { Ok(vec![pixel; len]) })
+        }
+
+        fn get_timeline_type(&self) -> AvailablePlugins {
+            AvailablePlugins::timeline_plugin_experience
+        }
+    }
+
+    #[rocket::async_test]
+    async fn covers_leave_out_hidden_connections() {
+        let server = TestServer::with_renderers(HashMap::from([(
+            AvailablePlugins::timeline_plugin_experience,
+            Box::new(HiddenRenderer) as Box<dyn PluginRenderer>,
+        )]))
+        .await;
+        let shared = server.create_experience("Shared", false).await;
+        let hidden = server.create_experience("Hidden", false).await;
+        let experience_manager = &server.experience_manager;
+        experience_manager
+            .set_acl(
+                &shared,
+                vec![AccessEntry {
+                    subject: AccessSubject::User("viewer".to_string()),
+                    role: AccessRole::Viewer,
+                }],
+            )
+            .await
+            .unwrap();
+        let connection = CompressedEvent {
+            title: "Hidden".to_string(),
+            time: Timing::Instant(Utc::now()),
+            data: serde_json::to_value(CompressedExperienceEvent::Experience(hidden.clone()))
+                .unwrap(),
+        };
+        experience_manager
+            .append_event(&shared, (AvailablePlugins::timeline_plugin_experience, connection))
+            .await
+            .unwrap();
+
+        let client = server
+            .client(rocket::build().mount(
+                "/api",
+                routes![
+                    login,
+                    experiences::entire_experience_cover,
+                    experiences::event_cover
+                ],
+            ))
+            .await;
+        let login = client
+            .post("/api/login")
+            .json(&LoginRequest {
+                name: "viewer".to_string(),
+                password: "viewer".to_string(),
+            })
+            .remote(remote())
+            .dispatch()
+            .await;
+        assert_eq!(login.status(), Status::Ok);
+
+        let cover = |path: String| client.get(path).remote(remote()).dispatch();
+        let response = cover(format!("/api/experience/{}/event/{}/cover", shared, hidden)).await;
+        assert_eq!(response.status(), Status::NotFound);
+        let response = cover(format!("/api/experience/{}/event/{}/cover", shared, shared)).await;
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = cover(format!("/api/experience/{}/cover/32/all", shared)).await;
+        assert_eq!(response.status(), Status::Ok);
+        let image = image::load_from_memory(&response.into_bytes().await.unwrap())
+            .unwrap()
+            .into_rgba8();
+        assert!(image.pixels().all(|v| v.0 != HIDDEN_PIXEL.to_le_bytes()));
+    }
 }
@@ -0,0 +1,155 @@
+use {
+    crate::{config::Config, experience_manager::ExperienceManager},
+    server_api::{
+        graph::ExperienceGraph,
+        graph_export::{export_graph, GraphFormat},
+        audit::{entry, AuditLog, SYSTEM_ACTOR},
+        refresh::{audit_refresh, refresh_all},
+        site_export,
+        timeline::TimelineClient,
+        users::hash_password,
+    },
+    shared::types::AuditAction,
+    std::{net::TcpListener, path::PathBuf, process},
+    tokio::fs::write,
+};
+
+pub async fn run(
+    command: &str,
+    args: &[String],
+    config: &Config,
+    experience_manager: &ExperienceManager,
+) {
+    match command {
+        "export-site" => {
+            let output = args
+                .first()
+                .map(PathBuf::from)
+                .unwrap_or(PathBuf::from("site"));
+            match site_export::export_site(experience_manager, &output).await {
+                Ok(v) => println!("Exported {} public experiences to {}", v, output.display()),
+                Err(e) => fail(&format!("Unable to export site: {}", e)),
+            }
+        }
+        "backup" => {
+            if server_running(config) {
+                fail(&format!(
+                    "Port {} is in use. Stop the server or create the backup with POST /api/backup",
+                    config.port
+                ));
+            }
+            match experience_manager.backup(&config.backups_folder).await {
+                Ok(v) => println!("Saved backup to {}", v.display()),
+                Err(e) => fail(&format!("Unable to create backup: {}", e)),
+            }
+        }
+        "restore" => {
+            let archive = match args.first() {
+                Some(v) => PathBuf::from(v),
+                None => fail("Missing backup archive: restore <archive>"),
+            };
+            if server_running(config) {
+                fail(&format!(
+                    "Port {} is in use. Stop the server before restoring a backup",
+                    config.port
+                ));
+            }
+            match experience_manager.restore(&archive).await {
+                Ok(_) => {
+                    AuditLog::new(config)
+                        .record(&entry(
+                            AuditAction::Restore,
+                            Some(SYSTEM_ACTOR.to_string()),
+                            None,
+                            None,
+                            Some(archive.display().to_string()),
+                        ))
+                        .await;
+                    println!("Restored backup {}", archive.display())
+                }
+                Err(e) => fail(&format!("Unable to restore backup: {}", e)),
+            }
+        }
+        "export-graph" => {
+            let format = match args.first().map(|v| v.parse::<GraphFormat>()) {
+                Some(Ok(v)) => v,
+                Some(Err(e)) => fail(&e),
+                None => fail("Missing format: export-graph <dot|graphml|json> [output file]"),
+            };
+            let graph = match ExperienceGraph::load(experience_manager, |_| true).await {
+                Ok(v) => v,
+                Err(e) => fail(&format!("Unable to load experience graph: {}", e)),
+            };
+            let exported = match export_graph(&graph, format) {
+                Ok(v) => v,
+                Err(e) => fail(&format!("Unable to export experience graph: {}", e)),
+            };
+            match args.get(1) {
+                Some(output) => match write(output, exported).await {
+                    Ok(_) => println!("Exported experience graph as {} to {}", format, output),
+                    Err(e) => fail(&format!("Unable to write experience graph: {}", e)),
+                },
+                None => print!("{}", exported),
+            }
+        }
+        "refresh" => {
+            let timeline = TimelineClient::new(config);
+            let audit = AuditLog::new(config);
+            let reports = match args.first() {
+                Some(id) => match experience_manager.refresh_events(id, &timeline, true).await {
+                    Ok(v) => {
+                        if !v.changed.is_empty() {
+                            audit_refresh(&audit, &v).await;
+                        }
+                        Ok(vec![v])
+                    }
+                    Err(e) => Err(e),
+                },
+                None => refresh_all(experience_manager, &timeline, &audit).await,
+            };
+            match reports {
+                Ok(reports) => {
+                    for report in reports {
+                        for change in report.changed {
+                            println!(
+                                "{} {}: \"{}\" -> \"{}\"",
+                                report.experience_id,
+                                change.event_id,
+                                change.old.title,
+                                change.new.title
+                            );
+                        }
+                        for missing in report.missing {
+                            println!("{} {}: missing in timeline", report.experience_id, missing);
+                        }
+                    }
+                }
+                Err(e) => fail(&format!("Unable to refresh events: {}", e)),
+            }
+        }
+        _ => fail(&format!(
+            "Unknown command: {}. Available commands: export-site [output folder], backup, restore <archive>, export-graph <dot|graphml|json> [output file], refresh [experience id], hash-password <password>",
+            command
+        )),
+    }
+}
+
+pub fn hash_password_command(args: &[String]) {
+    let password = match args.first() {
+        Some(v) => v,
+        None => fail("Missing password: hash-password <password>"),
+    };
+    match hash_password(password) {
+        Ok(v) => println!("{}", v),
+        Err(e) => fail(&format!("Unable to hash password: {}", e)),
+    }
+}
+
+fn server_running(config: &Config) -> bool {
+    TcpListener::bind(("127.0.0.1", config.port)).is_err()
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
@@ -3,47 +3,177 @@
 use {
     api::experiences, link::renderer::PluginRenderers, rocket::{
         catch, catchers,
+        fairing::AdHoc,
         fs::FileServer,
         response::{content, status},
-        routes, Request,
-    }, std::{collections::HashMap, sync::Arc}, tokio::{fs::File, io, sync::RwLock}
+        routes, Build, Request, Rocket,
+    }, server_api::{audit::AuditLog, positions::PositionManager, rate_limit::RateLimiter, refresh, timeline::TimelineClient, shares::ShareManager, tokens::TokenManager, users::UserManager, webhooks}, std::{collections::HashMap, sync::Arc, time::Duration}, tokio::{fs::File, io}
 };
 
 mod api;
+mod commands;
 pub use server_api::config;
 pub use server_api::experience_manager;
 pub use server_api::renderer;
 
-#[rocket::launch]
-async fn rocket() -> _ {
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.split_first() {
+        Some((command, args)) if command == "hash-password" => commands::hash_password_command(args),
+        Some((command, args)) => tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let (config, experience_manager) = init().await;
+                commands::run(command, args, &config, &experience_manager).await
+            }),
+        None => {
+            if let Err(e) = rocket::execute(async {
+                let (config, experience_manager) = init().await;
+                let users = UserManager::load(&config)
+                    .await
+                    .unwrap_or_else(|e| panic!("Unable to load users: {}", e));
+                let shares = ShareManager::load(&config)
+                    .await
+                    .unwrap_or_else(|e| panic!("Unable to load share links: {}", e));
+                let tokens = TokenManager::load(&config)
+                    .await
+                    .unwrap_or_else(|e| panic!("Unable to load API tokens: {}", e));
+                let positions = PositionManager::load(&config)
+                    .await
+                    .unwrap_or_else(|e| panic!("Unable to load navigator positions: {}", e));
+                rocket(config, experience_manager, users, shares, tokens, positions)
+                    .launch()
+                    .await
+            }) {
+                panic!("Unable to launch server: {}", e)
+            }
+        }
+    }
+}
+
+async fn init() -> (config::Config, experience_manager::ExperienceManager) {
     let config = config::Config::load()
         .await
         .unwrap_or_else(|e| panic!("Unable to init Config: {}", e));
     let experience_manager = experience_manager::ExperienceManager::new(&config, Arc::new(renderer::Renderer::new(PluginRenderers::init().await.renderers.into_values().map(|plugin| (plugin.get_timeline_type(), plugin)).collect::<HashMap<_, _>>()))).await;
+    (config, experience_manager)
+}
 
-    let figment = rocket::Config::figment().merge(("port", config.port));
+fn rocket(
+    config: config::Config,
+    experience_manager: experience_manager::ExperienceManager,
+    users: UserManager,
+    shares: ShareManager,
+    tokens: TokenManager,
+    positions: PositionManager,
+) -> Rocket<Build> {
+    let mut figment = rocket::Config::figment().merge(("port", config.port));
+    if let Some(secret_key) = &config.secret_key {
+        figment = figment.merge(("secret_key", secret_key));
+    }
+    figment = match &config.ip_header {
+        Some(ip_header) => figment.merge(("ip_header", ip_header)),
+        None => figment.merge(("ip_header", false)),
+    };
+    if config.timeline_password.is_none() {
+        eprintln!("No timeline_password configured, timeline requests are sent without a password");
+    }
+    let timeline = TimelineClient::new(&config);
+    let refresh_interval = config.refresh_interval_hours.filter(|v| *v > 0);
+    let refresh_experience_manager = experience_manager.clone();
+    let refresh_timeline = timeline.clone();
+    let changes = experience_manager.subscribe();
+    let webhooks = config.webhooks.clone();
+    let webhook_log = config.webhook_log.clone();
+    let audit = Arc::new(AuditLog::new(&config));
+    let refresh_audit = audit.clone();
     rocket::custom(figment)
         .register("/", catchers![not_found])
+        .attach(AdHoc::on_liftoff("Timeline refresh", move |_| {
+            Box::pin(async move {
+                if let Some(hours) = refresh_interval {
+                    tokio::spawn(refresh::schedule(
+                        refresh_experience_manager,
+                        refresh_timeline,
+                        refresh_audit,
+                        Duration::from_secs(hours * 60 * 60),
+                    ));
+                }
+            })
+        }))
+        .attach(AdHoc::on_liftoff("Webhooks", move |_| {
+            Box::pin(async move {
+                tokio::spawn(webhooks::dispatch(changes, webhooks, webhook_log));
+            })
+        }))
+        .manage(timeline)
+        .manage(users)
+        .manage(shares)
+        .manage(tokens)
+        .manage(positions)
+        .manage(Arc::new(RateLimiter::new(config.rate_limit.clone())))
+        .manage(audit)
         .manage(config)
         .manage(experience_manager)
-        .manage(api::navigator::NavigatorPosition(RwLock::new(
-            "0".to_string(),
-        )))
         .mount("/", FileServer::from("../frontend/dist/"))
         .mount(
             "/api",
             routes![
                 experiences::create_experience,
                 experiences::get_experience,
+                experiences::get_stats,
                 experiences::favorite_event,
+                experiences::set_event_private,
                 experiences::delete_event,
                 experiences::change_visibility,
+                experiences::get_acl,
+                experiences::set_acl,
+                experiences::get_details,
+                experiences::set_details,
                 experiences::append_event,
+                experiences::pull_events,
+                experiences::pull_plugins,
+                experiences::refresh_events,
                 experiences::cover,
                 experiences::entire_experience_cover,
+                experiences::event_cover,
                 api::navigator::get_connections,
                 api::navigator::get_position,
+                api::navigator::set_connection_details,
+                api::navigator::set_parent,
+                api::navigator::get_ancestors,
+                api::navigator::get_descendants,
+                api::graph::neighborhood,
+                api::graph::shortest_path,
+                api::graph::components,
+                api::graph::isolated,
+                api::graph::export,
+                api::templates::get_templates,
+                api::templates::save_template,
+                api::templates::delete_template,
+                api::backup::create_backup,
+                api::memories::today,
+                api::memories::on_date,
+                api::suggestions::get_suggestions,
+                api::suggestions::accept_suggestion,
+                api::live::events,
+                api::shares::get_shares,
+                api::shares::create_share,
+                api::shares::revoke_share,
+                api::shares::open_share,
+                api::audit::query,
+                api::tokens::get_tokens,
+                api::tokens::create_token,
+                api::tokens::revoke_token,
+                api::users::get_user,
+                api::users::get_users,
+                api::users::create_user,
+                api::users::delete_user,
                 api::timeline_url,
+                api::login,
+                api::logout,
                 api::auth_request
             ],
         )
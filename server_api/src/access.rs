@@ -0,0 +1,135 @@
+use {
+    crate::users::User,
+    shared::types::{AccessRole, AccessSubject, Experience},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccessLevel {
+    None,
+    Viewer,
+    Editor,
+    Owner,
+}
+
+pub fn access_level(experience: &Experience, user: Option<&User>) -> AccessLevel {
+    let public = if experience.public {
+        AccessLevel::Viewer
+    } else {
+        AccessLevel::None
+    };
+    let user = match user {
+        Some(v) => v,
+        None => return public,
+    };
+    if owns(&experience.owner, user) {
+        return AccessLevel::Owner;
+    }
+
+    experience
+        .acl
+        .iter()
+        .filter(|entry| match &entry.subject {
+            AccessSubject::User(name) => *name == user.name,
+            AccessSubject::Group(group) => user.groups.contains(group),
+        })
+        .map(|entry| match entry.role {
+            AccessRole::Viewer => AccessLevel::Viewer,
+            AccessRole::Editor => AccessLevel::Editor,
+        })
+        .fold(public, AccessLevel::max)
+}
+
+pub fn can_view(experience: &Experience, user: Option<&User>) -> bool {
+    access_level(experience, user) >= AccessLevel::Viewer
+}
+
+pub fn redact_private_events(experience: &mut Experience) {
+    for events in experience.events.values_mut() {
+        events.retain(|v| !v.private);
+    }
+}
+
+pub fn owns(owner: &Option<String>, user: &User) -> bool {
+    match owner {
+        Some(owner) => *owner == user.name,
+        None => user.admin,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, shared::types::AccessEntry, std::collections::HashMap};
+
+    fn experience(public: bool, owner: Option<&str>, acl: Vec<AccessEntry>) -> Experience {
+        Experience {
+            events: HashMap::new(),
+            public,
+            name: "experience".to_string(),
+            tags: Vec::new(),
+            metadata: HashMap::new(),
+            parent: None,
+            children: Vec::new(),
+            connection_details: HashMap::new(),
+            owner: owner.map(|v| v.to_string()),
+            acl,
+            created_time: None,
+        }
+    }
+
+    fn user(name: &str, admin: bool, groups: &[&str]) -> User {
+        User {
+            name: name.to_string(),
+            password: String::new(),
+            admin,
+            groups: groups.iter().map(|v| v.to_string()).collect(),
+            session_generation: 0,
+        }
+    }
+
+    fn entry(subject: AccessSubject, role: AccessRole) -> AccessEntry {
+        AccessEntry { subject, role }
+    }
+
+    #[test]
+    fn anonymous_callers_only_view_public_experiences() {
+        assert_eq!(access_level(&experience(false, None, Vec::new()), None), AccessLevel::None);
+        assert_eq!(access_level(&experience(true, None, Vec::new()), None), AccessLevel::Viewer);
+    }
+
+    #[test]
+    fn owners_and_admins_of_unowned_experiences_own() {
+        let alice = user("alice", false, &[]);
+        let admin = user("admin", true, &[]);
+        let owned = experience(false, Some("alice"), Vec::new());
+        assert_eq!(access_level(&owned, Some(&alice)), AccessLevel::Owner);
+        assert_eq!(access_level(&owned, Some(&admin)), AccessLevel::None);
+
+        let unowned = experience(false, None, Vec::new());
+        assert_eq!(access_level(&unowned, Some(&admin)), AccessLevel::Owner);
+        assert_eq!(access_level(&unowned, Some(&alice)), AccessLevel::None);
+    }
+
+    #[test]
+    fn acl_entries_grant_the_highest_role() {
+        let bob = user("bob", false, &["family"]);
+        let carol = user("carol", false, &["friends"]);
+        let shared = experience(
+            true,
+            Some("alice"),
+            vec![
+                entry(AccessSubject::Group("family".to_string()), AccessRole::Viewer),
+                entry(AccessSubject::User("bob".to_string()), AccessRole::Editor),
+            ],
+        );
+        assert_eq!(access_level(&shared, Some(&bob)), AccessLevel::Editor);
+        assert_eq!(access_level(&shared, Some(&carol)), AccessLevel::Viewer);
+
+        let private = experience(
+            false,
+            Some("alice"),
+            vec![entry(AccessSubject::Group("family".to_string()), AccessRole::Viewer)],
+        );
+        assert!(can_view(&private, Some(&bob)));
+        assert!(!can_view(&private, Some(&carol)));
+    }
+}
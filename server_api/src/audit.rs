@@ -0,0 +1,287 @@
+use {
+    crate::config::Config,
+    chrono::Utc,
+    shared::types::{AuditAction, AuditEntry, AuditQuery, ExperienceError, ExperienceResult},
+    std::{
+        collections::HashMap,
+        io::ErrorKind,
+        net::IpAddr,
+        path::PathBuf,
+        sync::Mutex as SyncMutex,
+        time::{Duration, Instant},
+    },
+    tokio::{
+        fs::{metadata, read_to_string, remove_file, rename, OpenOptions},
+        io::AsyncWriteExt,
+        sync::{Mutex, RwLock},
+    },
+};
+
+const DEFAULT_QUERY_LIMIT: usize = 500;
+pub const SYSTEM_ACTOR: &str = "system";
+const AUTH_FAILURE_WINDOW: Duration = Duration::from_secs(60);
+
+struct AuthFailures {
+    since: Instant,
+    suppressed: u32,
+}
+
+pub struct AuditLog {
+    path: PathBuf,
+    max_bytes: u64,
+    files: usize,
+    lock: Mutex<()>,
+    rotation: RwLock<()>,
+    auth_failures: SyncMutex<HashMap<Option<String>, AuthFailures>>,
+}
+
+pub fn entry(
+    action: AuditAction,
+    actor: Option<String>,
+    ip: Option<IpAddr>,
+    experience_id: Option<&str>,
+    details: Option<String>,
+) -> AuditEntry {
+    AuditEntry {
+        timestamp: Utc::now(),
+        action,
+        actor,
+        ip: ip.map(|v| v.to_string()),
+        experience_id: experience_id.map(|v| v.to_string()),
+        details,
+    }
+}
+
+impl AuditLog {
+    pub fn new(config: &Config) -> AuditLog {
+        AuditLog {
+            path: config.audit_log.clone(),
+            max_bytes: config.audit_log_max_bytes,
+            files: config.audit_log_files,
+            lock: Mutex::new(()),
+            rotation: RwLock::new(()),
+            auth_failures: SyncMutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn record(&self, entry: &AuditEntry) {
+        let mut line = String::new();
+        for entry in self.throttle(entry) {
+            match serde_json::to_string(&entry) {
+                Ok(v) => line.push_str(&v),
+                Err(e) => {
+                    eprintln!("Unable to serialize audit entry: {}", e);
+                    continue;
+                }
+            }
+            line.push('\n');
+        }
+        if line.is_empty() {
+            return;
+        }
+
+        let _guard = self.lock.lock().await;
+        if let Ok(v) = metadata(&self.path).await
+            && v.len() + line.len() as u64 > self.max_bytes
+        {
+            let _rotation = self.rotation.write().await;
+            if let Err(e) = self.rotate().await {
+                eprintln!("Unable to rotate audit log: {}", e);
+            }
+        }
+
+        let res = match OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+        {
+            Ok(mut file) => file.write_all(line.as_bytes()).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            eprintln!("Unable to write audit log: {}", e);
+        }
+    }
+
+    pub async fn query(&self, query: &AuditQuery) -> ExperienceResult<Vec<AuditEntry>> {
+        let _rotation = self.rotation.read().await;
+        let mut entries = Vec::new();
+        for index in (0..=self.files).rev() {
+            let content = match read_to_string(self.rotated_path(index)).await {
+                Ok(v) => v,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+            };
+            entries.extend(
+                content
+                    .lines()
+                    .filter_map(|v| serde_json::from_str::<AuditEntry>(v).ok())
+                    .filter(|v| matches(v, query)),
+            );
+        }
+
+        entries.reverse();
+        entries.truncate(query.limit.unwrap_or(DEFAULT_QUERY_LIMIT));
+        Ok(entries)
+    }
+
+    fn throttle(&self, entry: &AuditEntry) -> Vec<AuditEntry> {
+        let now = Instant::now();
+        let mut entries = Vec::new();
+        let mut failures = self.auth_failures.lock().unwrap();
+        failures.retain(|ip, v| {
+            if now - v.since < AUTH_FAILURE_WINDOW {
+                return true;
+            }
+            if v.suppressed > 0 {
+                entries.push(AuditEntry {
+                    timestamp: Utc::now(),
+                    action: AuditAction::AuthFailure,
+                    actor: None,
+                    ip: ip.clone(),
+                    experience_id: None,
+                    details: Some(format!("{} more authentication failures", v.suppressed)),
+                });
+            }
+            false
+        });
+
+        if entry.action != AuditAction::AuthFailure {
+            entries.push(entry.clone());
+            return entries;
+        }
+        match failures.get_mut(&entry.ip) {
+            Some(v) => v.suppressed += 1,
+            None => {
+                failures.insert(
+                    entry.ip.clone(),
+                    AuthFailures {
+                        since: now,
+                        suppressed: 0,
+                    },
+                );
+                entries.push(entry.clone());
+            }
+        }
+        entries
+    }
+
+    async fn rotate(&self) -> std::io::Result<()> {
+        if self.files == 0 {
+            return remove_file(&self.path).await;
+        }
+        match remove_file(self.rotated_path(self.files)).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        for index in (0..self.files).rev() {
+            match rename(self.rotated_path(index), self.rotated_path(index + 1)).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        if index == 0 {
+            return self.path.clone();
+        }
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        PathBuf::from(path)
+    }
+}
+
+fn matches(entry: &AuditEntry, query: &AuditQuery) -> bool {
+    query
+        .experience_id
+        .as_ref()
+        .is_none_or(|v| entry.experience_id.as_ref() == Some(v))
+        && query
+            .actor
+            .as_ref()
+            .is_none_or(|v| entry.actor.as_ref() == Some(v))
+        && query.action.is_none_or(|v| entry.action == v)
+        && query.since.is_none_or(|v| entry.timestamp >= v)
+        && query.until.is_none_or(|v| entry.timestamp <= v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(max_bytes: u64, files: usize) -> AuditLog {
+        let path = std::env::temp_dir()
+            .join(format!("audit-{}", uuid::Uuid::new_v4()))
+            .join("audit.log");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        AuditLog {
+            path,
+            max_bytes,
+            files,
+            lock: Mutex::new(()),
+            rotation: RwLock::new(()),
+            auth_failures: SyncMutex::new(HashMap::new()),
+        }
+    }
+
+    fn details(entries: &[AuditEntry]) -> Vec<&str> {
+        entries.iter().filter_map(|v| v.details.as_deref()).collect()
+    }
+
+    #[tokio::test]
+    async fn rotates_and_queries_newest_first() {
+        let log = log(200, 2);
+        for index in 0..8 {
+            log.record(&entry(AuditAction::Create, None, None, None, Some(index.to_string())))
+                .await;
+        }
+
+        assert!(log.rotated_path(1).exists());
+        assert!(log.rotated_path(2).exists());
+        assert!(!log.rotated_path(3).exists());
+        for index in 0..=2 {
+            assert!(std::fs::metadata(log.rotated_path(index)).unwrap().len() <= 200);
+        }
+
+        let entries = log.query(&AuditQuery::default()).await.unwrap();
+        let kept = details(&entries);
+        assert_eq!(kept.first(), Some(&"7"));
+        assert!(kept.len() < 8);
+        assert!(kept.windows(2).all(|v| v[0] > v[1]));
+
+        std::fs::remove_dir_all(log.path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn throttles_auth_failures_per_ip() {
+        let log = log(u64::MAX, 0);
+        let attacker: IpAddr = [10, 0, 0, 1].into();
+        let other: IpAddr = [10, 0, 0, 2].into();
+        for ip in [attacker, attacker, attacker, other] {
+            log.record(&entry(
+                AuditAction::AuthFailure,
+                None,
+                Some(ip),
+                None,
+                Some(ip.to_string()),
+            ))
+            .await;
+        }
+        let entries = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(details(&entries), ["10.0.0.2", "10.0.0.1"]);
+
+        log.auth_failures
+            .lock()
+            .unwrap()
+            .values_mut()
+            .for_each(|v| v.since -= AUTH_FAILURE_WINDOW);
+        log.record(&entry(AuditAction::Login, None, None, None, None)).await;
+        let entries = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(details(&entries), ["2 more authentication failures", "10.0.0.2", "10.0.0.1"]);
+
+        std::fs::remove_dir_all(log.path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,197 @@
+use {
+    chrono::Utc,
+    flate2::{read::GzDecoder, write::GzEncoder, Compression},
+    shared::types::{Experience, ExperienceError, ExperienceResult},
+    std::{
+        fs::{self, File},
+        io,
+        path::{Path, PathBuf},
+    },
+    tar::{Archive, Builder},
+};
+
+const EXPERIENCES_ENTRY: &str = "experiences";
+const COVERS_ENTRY: &str = "covers";
+
+pub fn write_archive(
+    archive: &Path,
+    experiences_folder: &Path,
+    covers_folder: &Path,
+) -> io::Result<()> {
+    let temp_archive = archive.with_extension("tmp");
+    let mut builder = Builder::new(GzEncoder::new(
+        File::create(&temp_archive)?,
+        Compression::default(),
+    ));
+    append_folder(&mut builder, EXPERIENCES_ENTRY, experiences_folder)?;
+    append_folder(&mut builder, COVERS_ENTRY, covers_folder)?;
+    builder.into_inner()?.finish()?;
+    fs::rename(temp_archive, archive)
+}
+
+fn append_folder(
+    builder: &mut Builder<GzEncoder<File>>,
+    entry: &str,
+    folder: &Path,
+) -> io::Result<()> {
+    builder.append_dir(entry, folder)?;
+    for file in fs::read_dir(folder)? {
+        let file = file?;
+        let file_name = file.file_name();
+        if !file.file_type()?.is_file() || Path::new(&file_name).extension() == Some("tmp".as_ref())
+        {
+            continue;
+        }
+        builder.append_path_with_name(file.path(), Path::new(entry).join(file_name))?;
+    }
+    Ok(())
+}
+
+struct Replaced {
+    folder: PathBuf,
+    old: Option<PathBuf>,
+    installed: bool,
+}
+
+pub async fn restore(
+    archive: &Path,
+    experiences_folder: &Path,
+    covers_folder: &Path,
+) -> ExperienceResult<()> {
+    let archive = archive.to_path_buf();
+    let experiences_folder = experiences_folder.to_path_buf();
+    let covers_folder = covers_folder.to_path_buf();
+    match tokio::task::spawn_blocking(move || {
+        restore_blocking(&archive, &experiences_folder, &covers_folder)
+    })
+    .await
+    {
+        Ok(v) => v,
+        Err(e) => Err(ExperienceError::FileError(e.to_string())),
+    }
+}
+
+fn restore_blocking(
+    archive: &Path,
+    experiences_folder: &Path,
+    covers_folder: &Path,
+) -> ExperienceResult<()> {
+    let staging = sibling(experiences_folder, "restore");
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| ExperienceError::UnableToWrite(e.to_string()))?;
+    }
+    fs::create_dir_all(&staging).map_err(|e| ExperienceError::UnableToWrite(e.to_string()))?;
+
+    let res = unpack(archive, &staging).and_then(|_| validate(&staging));
+    if let Err(e) = res {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let mut replaced = Vec::new();
+    let res = install(
+        &staging,
+        &[
+            (EXPERIENCES_ENTRY, experiences_folder),
+            (COVERS_ENTRY, covers_folder),
+        ],
+        &mut replaced,
+    );
+    if res.is_err() {
+        roll_back(&replaced);
+    }
+    let _ = fs::remove_dir_all(&staging);
+
+    res.map_err(|e| ExperienceError::UnableToWrite(e.to_string()))
+}
+
+fn install(
+    staging: &Path,
+    folders: &[(&str, &Path)],
+    replaced: &mut Vec<Replaced>,
+) -> io::Result<()> {
+    let timestamp = Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    for (entry, folder) in folders {
+        let mut old = None;
+        if folder.exists() {
+            let path = sibling(folder, &format!("{}.old", timestamp));
+            fs::rename(folder, &path)?;
+            old = Some(path);
+        }
+        replaced.push(Replaced {
+            folder: folder.to_path_buf(),
+            old,
+            installed: false,
+        });
+        fs::rename(staging.join(entry), folder)?;
+        if let Some(v) = replaced.last_mut() {
+            v.installed = true;
+        }
+    }
+    Ok(())
+}
+
+fn roll_back(replaced: &[Replaced]) {
+    for v in replaced.iter().rev() {
+        if v.installed
+            && let Err(e) = fs::remove_dir_all(&v.folder)
+        {
+            eprintln!("Unable to remove restored {}: {}", v.folder.display(), e);
+        }
+        if let Some(old) = &v.old
+            && let Err(e) = fs::rename(old, &v.folder)
+        {
+            eprintln!(
+                "Unable to move {} back to {}: {}",
+                old.display(),
+                v.folder.display(),
+                e
+            );
+        }
+    }
+}
+
+fn unpack(archive: &Path, staging: &Path) -> ExperienceResult<()> {
+    let file = File::open(archive).map_err(|e| ExperienceError::NotFound(e.to_string()))?;
+    Archive::new(GzDecoder::new(file))
+        .unpack(staging)
+        .map_err(|e| ExperienceError::FileError(format!("Unable to unpack backup: {}", e)))
+}
+
+fn validate(staging: &Path) -> ExperienceResult<()> {
+    if !staging.join(COVERS_ENTRY).is_dir() {
+        return Err(ExperienceError::FileError(
+            "Backup does not contain a covers folder".to_string(),
+        ));
+    }
+    let experiences = fs::read_dir(staging.join(EXPERIENCES_ENTRY)).map_err(|e| {
+        ExperienceError::FileError(format!("Backup does not contain experiences: {}", e))
+    })?;
+    for file in experiences {
+        let path = file
+            .map_err(|e| ExperienceError::FileError(e.to_string()))?
+            .path();
+        if !path.to_string_lossy().ends_with(".experience.json") {
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| ExperienceError::FileError(e.to_string()))?;
+        if let Err(e) = serde_json::from_str::<Experience>(&content) {
+            return Err(ExperienceError::ParsingError(format!(
+                "{} is not a valid experience: {}",
+                path.display(),
+                e
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn sibling(folder: &Path, suffix: &str) -> PathBuf {
+    let mut name = folder
+        .file_name()
+        .map(|v| v.to_os_string())
+        .unwrap_or_default();
+    name.push(format!(".{}", suffix));
+    folder.with_file_name(name)
+}
@@ -12,6 +12,119 @@ pub struct Config {
     pub experiences_folder: PathBuf,
     pub covers_folder: PathBuf,
     pub timeline_url: Url,
+    #[serde(default = "default_backups_folder")]
+    pub backups_folder: PathBuf,
+    #[serde(default)]
+    pub timeline_password: Option<String>,
+    #[serde(default)]
+    pub refresh_interval_hours: Option<u64>,
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    #[serde(default = "default_webhook_log")]
+    pub webhook_log: PathBuf,
+    #[serde(default = "default_users_file")]
+    pub users_file: PathBuf,
+    #[serde(default = "default_admin_name")]
+    pub admin_name: String,
+    #[serde(default = "default_shares_file")]
+    pub shares_file: PathBuf,
+    #[serde(default = "default_positions_file")]
+    pub positions_file: PathBuf,
+    #[serde(default)]
+    pub secret_key: Option<String>,
+    #[serde(default = "default_session_hours")]
+    pub session_hours: u64,
+    #[serde(default = "default_tokens_file")]
+    pub tokens_file: PathBuf,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub ip_header: Option<String>,
+    #[serde(default = "default_secure_cookies")]
+    pub secure_cookies: bool,
+    #[serde(default = "default_audit_log")]
+    pub audit_log: PathBuf,
+    #[serde(default = "default_audit_log_max_bytes")]
+    pub audit_log_max_bytes: u64,
+    #[serde(default = "default_audit_log_files")]
+    pub audit_log_files: usize,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Webhook {
+    pub url: Url,
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub ip_failures: u32,
+    pub global_failures: u32,
+    pub window_seconds: u64,
+    pub lockout_seconds: u64,
+    pub max_lockout_seconds: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            ip_failures: 5,
+            global_failures: 50,
+            window_seconds: 60,
+            lockout_seconds: 30,
+            max_lockout_seconds: 60 * 60,
+        }
+    }
+}
+
+fn default_backups_folder() -> PathBuf {
+    PathBuf::from("backups")
+}
+
+fn default_webhook_log() -> PathBuf {
+    PathBuf::from("webhooks.log")
+}
+
+fn default_users_file() -> PathBuf {
+    PathBuf::from("users.json")
+}
+
+fn default_admin_name() -> String {
+    "admin".to_string()
+}
+
+fn default_shares_file() -> PathBuf {
+    PathBuf::from("shares.json")
+}
+
+fn default_positions_file() -> PathBuf {
+    PathBuf::from("positions.json")
+}
+
+fn default_session_hours() -> u64 {
+    24 * 7
+}
+
+fn default_secure_cookies() -> bool {
+    true
+}
+
+fn default_tokens_file() -> PathBuf {
+    PathBuf::from("tokens.json")
+}
+
+fn default_audit_log() -> PathBuf {
+    PathBuf::from("audit.log")
+}
+
+fn default_audit_log_max_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_audit_log_files() -> usize {
+    5
 }
 
 impl Config {
@@ -1,29 +1,51 @@
 use {
-    chrono::Utc,
+    chrono::{NaiveDate, Utc},
     raqote::{DrawOptions, DrawTarget, Image},
+    serde_json::Value,
     timeline_types::{
         api::CompressedEvent,
-        timing::Timing,
+        timing::{TimeRange, Timing},
         available_plugins::AvailablePlugins
     },
     shared::types::{
-        CompressedExperienceEvent, Experience, ExperienceError, ExperienceEvent,
-        ExperienceResult,
+        AccessEntry, CompressedExperienceEvent, ConnectionDetails, EventRefresh, Experience,
+        ExperienceChange,
+        ExperienceDetails, ExperienceError, ExperienceEvent, ExperienceResult, ExperienceStats,
+        ExperienceTemplate,
+        RefreshReport,
     },    
-    std::{collections::HashMap, path::PathBuf, sync::Arc, thread},
+    std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        io::ErrorKind,
+        path::{Path, PathBuf},
+        sync::Arc,
+        thread,
+    },
     tokio::{
-        fs::{write, File},
+        fs::{create_dir_all, metadata, read_dir, remove_file, rename, write, File},
         io::AsyncReadExt,
-        sync::RwLock,
+        sync::{broadcast, RwLock},
     },
 };
 
-use crate::{config::Config, renderer::Renderer};
+use crate::{
+    backup,
+    config::Config,
+    renderer::Renderer,
+    timeline::TimelineClient,
+    timing::{timing_bounds, timing_from_bounds},
+};
+
+const CHANGES_CAPACITY: usize = 256;
+const EVENT_IDENTITY_KEYS: [&str; 6] = ["id", "uuid", "hash", "path", "url", "file"];
 
+#[derive(Clone)]
 pub struct ExperienceManager {
     experiences_folder: PathBuf,
     covers_folder: PathBuf,
-    cache: RwLock<HashMap<String, Arc<RwLock<Experience>>>>,
+    cache: Arc<RwLock<HashMap<String, Arc<RwLock<Experience>>>>>,
+    io_lock: Arc<RwLock<()>>,
+    changes: broadcast::Sender<ExperienceChange>,
     pub renderer: Arc<Renderer>,
 }
 
@@ -31,12 +53,22 @@ impl ExperienceManager {
     pub async fn new(config: &Config, renderer: Arc<Renderer>) -> Self {
         ExperienceManager {
             experiences_folder: config.experiences_folder.clone(),
-            cache: RwLock::new(HashMap::new()),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            io_lock: Arc::new(RwLock::new(())),
+            changes: broadcast::channel(CHANGES_CAPACITY).0,
             renderer: renderer,
             covers_folder: config.covers_folder.clone(),
         }
     }
 
+    pub fn subscribe(&self) -> broadcast::Receiver<ExperienceChange> {
+        self.changes.subscribe()
+    }
+
+    pub fn notify(&self, change: ExperienceChange) {
+        let _ = self.changes.send(change);
+    }
+
     pub async fn get_experience(&self, id: &str) -> ExperienceResult<Experience> {
         let found_experience;
         {
@@ -73,34 +105,316 @@ impl ExperienceManager {
         }
     }
 
-    pub async fn create_experience(&self, name: String, time: Timing) -> ExperienceResult<String> {
+    pub async fn list_experiences(&self) -> ExperienceResult<Vec<String>> {
+        let mut dir = match read_dir(&self.experiences_folder).await {
+            Ok(v) => v,
+            Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+        };
+        let mut ids = Vec::new();
+        loop {
+            match dir.next_entry().await {
+                Ok(Some(entry)) => {
+                    if let Ok(file_name) = entry.file_name().into_string()
+                        && let Some(id) = file_name.strip_suffix(".experience.json")
+                    {
+                        ids.push(id.to_string());
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+            }
+        }
+        Ok(ids)
+    }
+
+    pub async fn most_recent_experience(
+        &self,
+        visible: impl Fn(&Experience) -> bool,
+    ) -> ExperienceResult<Option<String>> {
+        let mut most_recent = None;
+        for id in self.list_experiences().await? {
+            let path = self
+                .experiences_folder
+                .join(format!("{}.experience.json", id));
+            let modified = match metadata(path).await.and_then(|v| v.modified()) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            if most_recent
+                .as_ref()
+                .is_some_and(|(_, time)| *time >= modified)
+            {
+                continue;
+            }
+            if let Ok(experience) = self.get_experience(&id).await
+                && visible(&experience)
+            {
+                most_recent = Some((id, modified));
+            }
+        }
+        Ok(most_recent.map(|(id, _)| id))
+    }
+
+    pub fn cover_path(&self, id: &str, small: bool) -> PathBuf {
+        if small {
+            self.covers_folder.join(format!("{}.small.png", id))
+        } else {
+            self.covers_folder.join(format!("{}.png", id))
+        }
+    }
+
+    pub async fn create_experience(
+        &self,
+        name: String,
+        time: Timing,
+        template: Option<&str>,
+        owner: Option<String>,
+    ) -> ExperienceResult<String> {
+        let template = match template {
+            Some(v) => Some(self.get_template(v).await?),
+            None => None,
+        };
+        let name = match &template {
+            Some(template) => apply_name_pattern(&template.name_pattern, &name, &time),
+            None => name,
+        };
+
         let id = uuid::Uuid::new_v4().to_string();
         let mut events = HashMap::new();
         events.insert(
             AvailablePlugins::timeline_plugin_experience,
             vec![ExperienceEvent {
                 event: CompressedEvent {
-                    time,
+                    time: time.clone(),
                     title: name.clone(),
                     data: serde_json::to_value(CompressedExperienceEvent::Experience(id.clone()))
                         .unwrap(),
                 },
                 favorite: false,
+                private: false,
                 id: id.clone(),
             }],
         );
 
-        let experience = Experience {
+        let mut experience = Experience {
             name,
             events,
             public: false,
+            tags: Vec::new(),
+            metadata: HashMap::new(),
+            parent: None,
+            children: Vec::new(),
+            connection_details: HashMap::new(),
+            owner,
+            acl: Vec::new(),
+            created_time: Some(time),
         };
 
+        if let Some(template) = &template {
+            experience.public = template.public;
+            experience.tags = template.tags.clone();
+            experience.metadata = template.metadata.clone();
+        }
+
         self.save_experience(&id, experience).await?;
 
+        if let Some(template) = template {
+            for connection in template.connections {
+                let connected_experience = match self.get_experience(&connection).await {
+                    Ok(v) => v,
+                    Err(ExperienceError::NotFound(_)) => continue,
+                    Err(e) => return Err(e),
+                };
+                self.append_event(
+                    &id,
+                    (
+                        AvailablePlugins::timeline_plugin_experience,
+                        CompressedEvent {
+                            time: experience_time(&connected_experience, &connection)
+                                .unwrap_or(Timing::Instant(Utc::now())),
+                            title: connected_experience.name,
+                            data: serde_json::to_value(CompressedExperienceEvent::Experience(
+                                connection.clone(),
+                            ))?,
+                        },
+                    ),
+                )
+                .await?;
+            }
+        }
+
+        self.notify(ExperienceChange::Created {
+            experience_id: id.clone(),
+        });
+
         Ok(id)
     }
 
+    pub async fn discard_experience(&self, id: &str) -> ExperienceResult<()> {
+        let experience = self.get_experience(id).await?;
+        let connected = experience
+            .events
+            .get(&AvailablePlugins::timeline_plugin_experience)
+            .is_some_and(|v| v.iter().any(|v| v.id != id));
+        if connected || experience.parent.is_some() || !experience.children.is_empty() {
+            return Err(ExperienceError::OperationNowAllowed(
+                "Only unconnected experiences can be discarded".to_string(),
+            ));
+        }
+
+        let _io_guard = self.io_lock.write().await;
+        self.cache.write().await.remove(id);
+        for path in [
+            self.experiences_folder
+                .join(format!("{}.experience.json", id)),
+            self.cover_path(id, false),
+            self.cover_path(id, true),
+        ] {
+            match remove_file(path).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => {
+                    return Err(ExperienceError::UnableToWrite(e.to_string()))
+                }
+                _ => {}
+            }
+        }
+        self.notify(ExperienceChange::Deleted {
+            experience_id: id.to_string(),
+        });
+        Ok(())
+    }
+
+    pub async fn get_stats(
+        &self,
+        id: &str,
+        experience: &Experience,
+        redacted: bool,
+    ) -> ExperienceResult<ExperienceStats> {
+        let mut events = HashMap::new();
+        let mut events_per_day = BTreeMap::new();
+        let mut favorites = 0;
+        for (plugin, plugin_events) in experience.events.iter() {
+            if *plugin == AvailablePlugins::timeline_plugin_experience {
+                continue;
+            }
+            events.insert(plugin.clone(), plugin_events.len());
+            for event in plugin_events {
+                if event.favorite {
+                    favorites += 1;
+                }
+                let day = timing_bounds(&event.event.time).0.date_naive();
+                *events_per_day.entry(day).or_insert(0) += 1;
+            }
+        }
+
+        let mut size = if redacted {
+            serde_json::to_string(experience)?.len() as u64
+        } else {
+            metadata(self.experiences_folder.join(format!("{}.experience.json", id)))
+                .await
+                .map(|v| v.len())
+                .unwrap_or(0)
+        };
+        for path in [self.cover_path(id, false), self.cover_path(id, true)] {
+            if let Ok(v) = metadata(path).await {
+                size += v.len();
+            }
+        }
+
+        Ok(ExperienceStats {
+            events,
+            favorites,
+            time: experience_time(experience, id),
+            events_per_day: fill_days(events_per_day),
+            connections: experience
+                .events
+                .get(&AvailablePlugins::timeline_plugin_experience)
+                .map(|v| v.iter().filter(|v| v.id != id).count())
+                .unwrap_or(0),
+            size,
+        })
+    }
+
+    pub async fn save_template(
+        &self,
+        experience_id: &str,
+        name: String,
+        name_pattern: Option<String>,
+        owner: Option<String>,
+    ) -> ExperienceResult<String> {
+        let experience = self.get_experience(experience_id).await?;
+        let template = ExperienceTemplate {
+            name,
+            name_pattern: name_pattern.unwrap_or(experience.name.clone()),
+            tags: experience.tags,
+            public: experience.public,
+            metadata: experience.metadata,
+            connections: experience
+                .events
+                .get(&AvailablePlugins::timeline_plugin_experience)
+                .map(|v| {
+                    v.iter()
+                        .filter(|v| v.id != experience_id)
+                        .map(|v| v.id.clone())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            owner,
+        };
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let _io_guard = self.io_lock.read().await;
+        match write(self.template_path(&id), serde_json::to_string(&template)?).await {
+            Ok(_) => Ok(id),
+            Err(e) => Err(ExperienceError::UnableToWrite(e.to_string())),
+        }
+    }
+
+    pub async fn get_template(&self, id: &str) -> ExperienceResult<ExperienceTemplate> {
+        let mut file = match File::open(self.template_path(id)).await {
+            Ok(v) => v,
+            Err(e) => return Err(ExperienceError::NotFound(e.to_string())),
+        };
+        let mut template_file_content = String::new();
+        if let Err(e) = file.read_to_string(&mut template_file_content).await {
+            return Err(ExperienceError::FileError(e.to_string()));
+        }
+        Ok(serde_json::from_str(&template_file_content)?)
+    }
+
+    pub async fn get_templates(&self) -> ExperienceResult<HashMap<String, ExperienceTemplate>> {
+        let mut dir = match read_dir(&self.experiences_folder).await {
+            Ok(v) => v,
+            Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+        };
+        let mut templates = HashMap::new();
+        loop {
+            match dir.next_entry().await {
+                Ok(Some(entry)) => {
+                    if let Ok(file_name) = entry.file_name().into_string()
+                        && let Some(id) = file_name.strip_suffix(".template.json")
+                    {
+                        templates.insert(id.to_string(), self.get_template(id).await?);
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+            }
+        }
+        Ok(templates)
+    }
+
+    pub async fn delete_template(&self, id: &str) -> ExperienceResult<()> {
+        let _io_guard = self.io_lock.read().await;
+        match remove_file(self.template_path(id)).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ExperienceError::NotFound(e.to_string())),
+        }
+    }
+
+    fn template_path(&self, id: &str) -> PathBuf {
+        self.experiences_folder.join(format!("{}.template.json", id))
+    }
+
     pub async fn delete_event(
         &self,
         experience_id: &str,
@@ -122,6 +436,19 @@ impl ExperienceManager {
                     .await.unwrap_or_else(|e| panic!("Unable to delete a connection: Deleting the connection from the counterpart failed: {}. This is my experience id: {}. This is the experience id of the counter part: {}", e, experience_id, id));
             }
         }
+        if let Some((plugin, event)) = &res {
+            self.notify(if *plugin == AvailablePlugins::timeline_plugin_experience {
+                ExperienceChange::Disconnected {
+                    experience_id: experience_id.to_string(),
+                    connected_id: event.id.clone(),
+                }
+            } else {
+                ExperienceChange::EventDeleted {
+                    experience_id: experience_id.to_string(),
+                    event_id: event.id.clone(),
+                }
+            });
+        }
         Ok(res)
     }
 
@@ -150,8 +477,13 @@ impl ExperienceManager {
         {
             experience.events.remove(&deleted_event_plugin);
         }
+        experience.connection_details.remove(event_id);
 
+        let span = update_span(experience_id, &mut experience);
         self.save_experience(experience_id, experience).await?;
+        if let Some(span) = span {
+            self.propagate_span(experience_id, span).await?;
+        }
         Ok(deleted_event)
     }
 
@@ -170,6 +502,36 @@ impl ExperienceManager {
             })
         });
         self.save_experience(experience_id, experience).await?;
+        self.notify(ExperienceChange::Edited {
+            experience_id: experience_id.to_string(),
+        });
+        Ok(())
+    }
+
+    pub async fn set_event_private(
+        &self,
+        experience_id: &str,
+        event_id: &str,
+        private: bool,
+    ) -> ExperienceResult<()> {
+        let mut experience = self.get_experience(experience_id).await?;
+        match experience
+            .events
+            .values_mut()
+            .flatten()
+            .find(|v| v.id == event_id)
+        {
+            Some(v) => v.private = private,
+            None => return Err(ExperienceError::NotFound(event_id.to_string())),
+        }
+        let span = update_span(experience_id, &mut experience);
+        self.save_experience(experience_id, experience).await?;
+        if let Some(span) = span {
+            self.propagate_span(experience_id, span).await?;
+        }
+        self.notify(ExperienceChange::Edited {
+            experience_id: experience_id.to_string(),
+        });
         Ok(())
     }
 
@@ -202,6 +564,7 @@ impl ExperienceManager {
 
             let experience_a_experience_event = ExperienceEvent {
                 favorite: false,
+                private: false,
                 id: experience_b_id.clone(),
                 event: CompressedEvent {
                     data: serde_json::to_value(CompressedExperienceEvent::Experience(
@@ -229,6 +592,7 @@ impl ExperienceManager {
 
             let experience_b_experience_event = ExperienceEvent {
                 favorite: false,
+                private: false,
                 id: experience_a_id.clone(),
                 event: CompressedEvent {
                     data: serde_json::to_value(CompressedExperienceEvent::Experience(
@@ -271,6 +635,11 @@ impl ExperienceManager {
                     )
                 });
 
+            self.notify(ExperienceChange::Connected {
+                experience_id: experience_b_id,
+                connected_id: experience_a_id.clone(),
+            });
+
             Ok(experience_a_id)
         } else {
             self.append_event_unchecked(experience_id, event).await
@@ -288,6 +657,7 @@ impl ExperienceManager {
         let experience_event = ExperienceEvent {
             id: id.clone(),
             favorite: false,
+            private: false,
             event: event.1,
         };
 
@@ -298,11 +668,179 @@ impl ExperienceManager {
             }
         }
 
+        let span = update_span(experience_id, &mut experience);
         self.save_experience(experience_id, experience).await?;
+        if let Some(span) = span {
+            self.propagate_span(experience_id, span).await?;
+        }
+        self.notify(ExperienceChange::Edited {
+            experience_id: experience_id.to_string(),
+        });
 
         Ok(id)
     }
 
+    pub async fn append_events(
+        &self,
+        experience_id: &str,
+        events: Vec<(AvailablePlugins, CompressedEvent)>,
+    ) -> ExperienceResult<Vec<String>> {
+        let mut experience = self.get_experience(experience_id).await?;
+
+        let mut ids = Vec::new();
+        for (plugin, event) in events {
+            if plugin == AvailablePlugins::timeline_plugin_experience {
+                return Err(ExperienceError::OperationNowAllowed(
+                    "Connections can not be appended in bulk".to_string(),
+                ));
+            }
+            if experience
+                .events
+                .get(&plugin)
+                .is_some_and(|v| v.iter().any(|v| v.event == event))
+            {
+                continue;
+            }
+            let id = uuid::Uuid::new_v4().to_string();
+            experience.events.entry(plugin).or_default().push(ExperienceEvent {
+                id: id.clone(),
+                favorite: false,
+                private: false,
+                event,
+            });
+            ids.push(id);
+        }
+
+        if ids.is_empty() {
+            return Ok(ids);
+        }
+
+        let span = update_span(experience_id, &mut experience);
+        self.save_experience(experience_id, experience).await?;
+        if let Some(span) = span {
+            self.propagate_span(experience_id, span).await?;
+        }
+        self.notify(ExperienceChange::Edited {
+            experience_id: experience_id.to_string(),
+        });
+
+        Ok(ids)
+    }
+
+    pub async fn pull_events(
+        &self,
+        experience_id: &str,
+        timeline: &TimelineClient,
+        time: &Timing,
+        plugins: &HashSet<AvailablePlugins>,
+    ) -> ExperienceResult<Vec<String>> {
+        let (start, end) = timing_bounds(time);
+        let events = timeline
+            .get_events(&TimeRange { start, end })
+            .await?
+            .into_iter()
+            .filter(|(plugin, _)| {
+                *plugin != AvailablePlugins::timeline_plugin_experience
+                    && (plugins.is_empty() || plugins.contains(plugin))
+            })
+            .flat_map(|(plugin, events)| events.into_iter().map(move |v| (plugin.clone(), v)))
+            .collect();
+
+        self.append_events(experience_id, events).await
+    }
+
+    pub async fn refresh_events(
+        &self,
+        experience_id: &str,
+        timeline: &TimelineClient,
+        apply: bool,
+    ) -> ExperienceResult<RefreshReport> {
+        let mut experience = self.get_experience(experience_id).await?;
+        let mut report = RefreshReport {
+            experience_id: experience_id.to_string(),
+            changed: Vec::new(),
+            missing: Vec::new(),
+            applied: apply,
+        };
+        let span = match compute_span(&experience, true) {
+            Some(v) => v,
+            None => return Ok(report),
+        };
+
+        let (start, end) = timing_bounds(&span);
+        let fetched = timeline.get_events(&TimeRange { start, end }).await?;
+
+        for (plugin, events) in experience.events.iter_mut() {
+            if *plugin == AvailablePlugins::timeline_plugin_experience {
+                continue;
+            }
+            let candidates = fetched.get(plugin).map(|v| v.as_slice()).unwrap_or_default();
+            for event in events.iter_mut() {
+                match find_refreshed_event(&event.event, candidates) {
+                    Some(v) if *v == event.event => {}
+                    Some(v) => {
+                        report.changed.push(EventRefresh {
+                            event_id: event.id.clone(),
+                            plugin: plugin.clone(),
+                            old: event.event.clone(),
+                            new: v.clone(),
+                        });
+                        if apply {
+                            event.event = v.clone();
+                        }
+                    }
+                    None => report.missing.push(event.id.clone()),
+                }
+            }
+        }
+
+        if apply && !report.changed.is_empty() {
+            let span = update_span(experience_id, &mut experience);
+            self.save_experience(experience_id, experience).await?;
+            if let Some(span) = span {
+                self.propagate_span(experience_id, span).await?;
+            }
+            self.notify(ExperienceChange::Edited {
+                experience_id: experience_id.to_string(),
+            });
+        }
+
+        Ok(report)
+    }
+
+    async fn propagate_span(&self, experience_id: &str, span: Timing) -> ExperienceResult<()> {
+        let experience = self.get_experience(experience_id).await?;
+        let connections = experience
+            .events
+            .get(&AvailablePlugins::timeline_plugin_experience)
+            .map(|v| {
+                v.iter()
+                    .filter(|v| v.id != experience_id)
+                    .map(|v| v.id.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for connection in connections {
+            let mut connected_experience = match self.get_experience(&connection).await {
+                Ok(v) => v,
+                Err(ExperienceError::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            if let Some(event) = connected_experience
+                .events
+                .get_mut(&AvailablePlugins::timeline_plugin_experience)
+                .and_then(|v| v.iter_mut().find(|v| v.id == experience_id))
+            {
+                event.event.time = span.clone();
+                self.save_experience(&connection, connected_experience)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn set_experience_visibility(
         &self,
         id: &str,
@@ -311,7 +849,164 @@ impl ExperienceManager {
         let mut experience = self.get_experience(id).await?;
         experience.public = visibility;
 
-        self.save_experience(id, experience).await
+        self.save_experience(id, experience).await?;
+        self.notify(ExperienceChange::VisibilityChanged {
+            experience_id: id.to_string(),
+            public: visibility,
+        });
+        Ok(())
+    }
+
+    pub async fn set_acl(&self, id: &str, acl: Vec<AccessEntry>) -> ExperienceResult<()> {
+        let mut experience = self.get_experience(id).await?;
+        experience.acl = acl;
+
+        self.save_experience(id, experience).await?;
+        self.notify(ExperienceChange::Edited {
+            experience_id: id.to_string(),
+        });
+        Ok(())
+    }
+
+    pub async fn set_details(&self, id: &str, details: ExperienceDetails) -> ExperienceResult<()> {
+        let mut experience = self.get_experience(id).await?;
+        experience.tags = details.tags;
+        experience.metadata = details.metadata;
+
+        self.save_experience(id, experience).await?;
+        self.notify(ExperienceChange::Edited {
+            experience_id: id.to_string(),
+        });
+        Ok(())
+    }
+
+    pub async fn release_experiences(&self, owner: &str) -> ExperienceResult<()> {
+        for id in self.list_experiences().await? {
+            let mut experience = self.get_experience(&id).await?;
+            if experience.owner.as_deref() == Some(owner) {
+                experience.owner = None;
+                self.save_experience(&id, experience).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn set_connection_details(
+        &self,
+        experience_id: &str,
+        connected_experience_id: &str,
+        details: ConnectionDetails,
+    ) -> ExperienceResult<()> {
+        let mut experience = self.get_experience(experience_id).await?;
+        let mut connected_experience = self.get_experience(connected_experience_id).await?;
+
+        if experience_id == connected_experience_id
+            || !experience
+                .events
+                .get(&AvailablePlugins::timeline_plugin_experience)
+                .map(|v| v.iter().any(|v| v.id == connected_experience_id))
+                .unwrap_or(false)
+        {
+            return Err(ExperienceError::NotFound(format!(
+                "{} is not connected to {}",
+                connected_experience_id, experience_id
+            )));
+        }
+
+        connected_experience.connection_details.insert(
+            experience_id.to_string(),
+            ConnectionDetails {
+                relationship: details.relationship.inverse(),
+                label: details.label.clone(),
+            },
+        );
+        experience
+            .connection_details
+            .insert(connected_experience_id.to_string(), details);
+
+        self.save_experience(connected_experience_id, connected_experience)
+            .await?;
+        self.save_experience(experience_id, experience).await?;
+        self.notify(ExperienceChange::Edited {
+            experience_id: experience_id.to_string(),
+        });
+        Ok(())
+    }
+
+    pub async fn set_parent(&self, id: &str, parent: Option<String>) -> ExperienceResult<()> {
+        let mut experience = self.get_experience(id).await?;
+
+        if let Some(parent) = &parent {
+            if parent == id {
+                return Err(ExperienceError::OperationNowAllowed(
+                    "An experience can not be its own parent".to_string(),
+                ));
+            }
+            if self.get_ancestors(parent).await?.iter().any(|v| v == id) {
+                return Err(ExperienceError::OperationNowAllowed(format!(
+                    "Setting {} as parent of {} would create a cycle",
+                    parent, id
+                )));
+            }
+            self.get_experience(parent).await?;
+        }
+
+        if let Some(old_parent) = experience.parent.take()
+            && let Ok(mut old_parent_experience) = self.get_experience(&old_parent).await
+        {
+            old_parent_experience.children.retain(|v| v != id);
+            self.save_experience(&old_parent, old_parent_experience)
+                .await?;
+        }
+
+        if let Some(parent) = &parent {
+            let mut parent_experience = self.get_experience(parent).await?;
+            if !parent_experience.children.iter().any(|v| v == id) {
+                parent_experience.children.push(id.to_string());
+            }
+            self.save_experience(parent, parent_experience).await?;
+        }
+
+        experience.parent = parent;
+        self.save_experience(id, experience).await?;
+        self.notify(ExperienceChange::Edited {
+            experience_id: id.to_string(),
+        });
+        Ok(())
+    }
+
+    pub async fn get_ancestors(&self, id: &str) -> ExperienceResult<Vec<String>> {
+        let mut ancestors: Vec<String> = Vec::new();
+        let mut current = self.get_experience(id).await?.parent;
+        while let Some(parent) = current {
+            if parent == id || ancestors.contains(&parent) {
+                break;
+            }
+            current = match self.get_experience(&parent).await {
+                Ok(v) => v.parent,
+                Err(ExperienceError::NotFound(_)) => None,
+                Err(e) => return Err(e),
+            };
+            ancestors.push(parent);
+        }
+        Ok(ancestors)
+    }
+
+    pub async fn get_descendants(&self, id: &str) -> ExperienceResult<Vec<String>> {
+        let mut descendants: Vec<String> = Vec::new();
+        let mut queue = self.get_experience(id).await?.children;
+        while let Some(child) = queue.pop() {
+            if child == id || descendants.contains(&child) {
+                continue;
+            }
+            match self.get_experience(&child).await {
+                Ok(v) => queue.extend(v.children),
+                Err(ExperienceError::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            }
+            descendants.push(child);
+        }
+        Ok(descendants)
     }
 
     async fn save_experience(&self, id: &str, experience: Experience) -> ExperienceResult<()> {
@@ -331,17 +1026,62 @@ impl ExperienceManager {
         let path = self
             .experiences_folder
             .join(format!("{}.experience.json", id));
-        let res = match write(path, serde_json::to_string(experience)?).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(ExperienceError::UnableToWrite(e.to_string())),
+        let temp_path = self
+            .experiences_folder
+            .join(format!("{}.experience.json.tmp", id));
+        let content = serde_json::to_string(experience)?;
+        let res = {
+            let _io_guard = self.io_lock.read().await;
+            match write(&temp_path, content).await {
+                Ok(_) => rename(&temp_path, path)
+                    .await
+                    .map_err(|e| ExperienceError::UnableToWrite(e.to_string())),
+                Err(e) => Err(ExperienceError::UnableToWrite(e.to_string())),
+            }
         };
         self.generate_experience_cover(id.to_string(), experience.clone());
         res
     }
 
+    pub async fn backup(&self, backups_folder: &Path) -> ExperienceResult<PathBuf> {
+        if let Err(e) = create_dir_all(backups_folder).await {
+            return Err(ExperienceError::UnableToWrite(e.to_string()));
+        }
+        let archive = backups_folder.join(format!(
+            "experiences-backup-{}.tar.gz",
+            Utc::now().format("%Y-%m-%dT%H-%M-%S")
+        ));
+
+        let _io_guard = self.io_lock.write().await;
+        let experiences_folder = self.experiences_folder.clone();
+        let covers_folder = self.covers_folder.clone();
+        let archive_path = archive.clone();
+        match tokio::task::spawn_blocking(move || {
+            backup::write_archive(&archive_path, &experiences_folder, &covers_folder)
+        })
+        .await
+        {
+            Ok(Ok(_)) => Ok(archive),
+            Ok(Err(e)) => Err(ExperienceError::UnableToWrite(e.to_string())),
+            Err(e) => Err(ExperienceError::UnableToWrite(e.to_string())),
+        }
+    }
+
+    pub async fn restore(&self, archive: &Path) -> ExperienceResult<()> {
+        let _io_guard = self.io_lock.write().await;
+        let res = backup::restore(archive, &self.experiences_folder, &self.covers_folder).await;
+        self.cache.write().await.clear();
+        res
+    }
+
     fn generate_experience_cover(&self, id: String, experience: Experience) {
         let renderer = self.renderer.clone();
         let covers_folder = self.covers_folder.clone();
+        let experience_path = self
+            .experiences_folder
+            .join(format!("{}.experience.json", id));
+        let io_lock = self.io_lock.clone();
+        let changes = self.changes.clone();
 
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -354,6 +1094,10 @@ impl ExperienceManager {
             local.block_on(&rt, async move {
                 let _ = tokio::task::spawn_local(async move {
                     let dt = renderer.render_experience(&experience, 500).await;
+                    let _io_guard = io_lock.read().await;
+                    if !experience_path.exists() {
+                        return;
+                    }
                     if let Err(e) = dt.write_png(covers_folder.join(format!("{}.png", id))) {
                         eprintln!("Unable to save big cover: {}", e);
                     }
@@ -378,9 +1122,240 @@ impl ExperienceManager {
                     {
                         eprintln!("Unable to save small cover: {}", e);
                     }
+                    let _ = changes.send(ExperienceChange::CoverRegenerated { experience_id: id });
                 })
                 .await;
             });
         });
     }
 }
+
+pub fn experience_time(experience: &Experience, id: &str) -> Option<Timing> {
+    experience
+        .events
+        .get(&AvailablePlugins::timeline_plugin_experience)
+        .and_then(|v| v.iter().find(|v| v.id == id).map(|v| v.event.time.clone()))
+}
+
+pub fn compute_span(experience: &Experience, include_private: bool) -> Option<Timing> {
+    let (start, end) = experience
+        .events
+        .iter()
+        .filter(|(plugin, _)| **plugin != AvailablePlugins::timeline_plugin_experience)
+        .flat_map(|(_, events)| events.iter())
+        .filter(|v| include_private || !v.private)
+        .map(|v| timing_bounds(&v.event.time))
+        .fold(None, |span, (start, end)| match span {
+            None => Some((start, end)),
+            Some((span_start, span_end)) => Some((start.min(span_start), end.max(span_end))),
+        })?;
+    Some(timing_from_bounds(start, end))
+}
+
+fn fill_days(events_per_day: BTreeMap<NaiveDate, usize>) -> BTreeMap<String, usize> {
+    let (first, last) = match (
+        events_per_day.first_key_value(),
+        events_per_day.last_key_value(),
+    ) {
+        (Some(first), Some(last)) => (*first.0, *last.0),
+        _ => return BTreeMap::new(),
+    };
+    first
+        .iter_days()
+        .take_while(|v| *v <= last)
+        .map(|v| {
+            (
+                v.format("%Y-%m-%d").to_string(),
+                events_per_day.get(&v).cloned().unwrap_or(0),
+            )
+        })
+        .collect()
+}
+
+fn update_span(experience_id: &str, experience: &mut Experience) -> Option<Timing> {
+    let span = compute_span(experience, false).or_else(|| experience.created_time.clone());
+    let own_event = experience
+        .events
+        .get_mut(&AvailablePlugins::timeline_plugin_experience)?
+        .iter_mut()
+        .find(|v| v.id == experience_id)?;
+    let span = span.unwrap_or_else(|| Timing::Instant(timing_bounds(&own_event.event.time).0));
+    if own_event.event.time == span {
+        return None;
+    }
+    own_event.event.time = span.clone();
+    Some(span)
+}
+
+fn find_refreshed_event<'a>(
+    event: &CompressedEvent,
+    candidates: &'a [CompressedEvent],
+) -> Option<&'a CompressedEvent> {
+    if let Some((key, identity)) = event_identity(&event.data) {
+        return candidates.iter().find(|v| v.data.get(key) == Some(identity));
+    }
+    if let Some(v) = candidates.iter().find(|v| v.data == event.data) {
+        return Some(v);
+    }
+    let mut same_event = candidates
+        .iter()
+        .filter(|v| v.time == event.time && v.title == event.title);
+    match (same_event.next(), same_event.next()) {
+        (Some(v), None) => Some(v),
+        _ => None,
+    }
+}
+
+fn event_identity(data: &Value) -> Option<(&'static str, &Value)> {
+    EVENT_IDENTITY_KEYS
+        .iter()
+        .find_map(|key| data.get(key).filter(|v| !v.is_null()).map(|v| (*key, v)))
+}
+
+fn apply_name_pattern(pattern: &str, name: &str, time: &Timing) -> String {
+    let (start, _) = timing_bounds(time);
+    pattern
+        .replace("{name}", name)
+        .replace("{year}", &start.format("%Y").to_string())
+        .replace("{month}", &start.format("%m").to_string())
+        .replace("{day}", &start.format("%d").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, chrono::TimeZone, serde_json::json};
+
+    fn event(title: &str, hour: u32, data: Value) -> CompressedEvent {
+        CompressedEvent {
+            title: title.to_string(),
+            time: Timing::Instant(Utc.with_ymd_and_hms(2024, 5, 1, hour, 0, 0).unwrap()),
+            data,
+        }
+    }
+
+    fn experience_event(id: &str, event: CompressedEvent) -> ExperienceEvent {
+        ExperienceEvent {
+            favorite: false,
+            private: false,
+            id: id.to_string(),
+            event,
+        }
+    }
+
+    #[test]
+    fn span_resets_after_removing_all_events_without_created_time() {
+        let mut own_event = event("Trip", 10, Value::Null);
+        own_event.time = Timing::Range(TimeRange {
+            start: Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2024, 5, 3, 18, 0, 0).unwrap(),
+        });
+        let mut experience = Experience {
+            events: HashMap::from([(
+                AvailablePlugins::timeline_plugin_experience,
+                vec![experience_event("trip", own_event)],
+            )]),
+            public: false,
+            name: "Trip".to_string(),
+            tags: Vec::new(),
+            metadata: HashMap::new(),
+            parent: None,
+            children: Vec::new(),
+            connection_details: HashMap::new(),
+            owner: None,
+            acl: Vec::new(),
+            created_time: None,
+        };
+
+        let start = Timing::Instant(Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap());
+        assert_eq!(update_span("trip", &mut experience), Some(start.clone()));
+        assert_eq!(
+            experience.events[&AvailablePlugins::timeline_plugin_experience][0]
+                .event
+                .time,
+            start
+        );
+        assert_eq!(update_span("trip", &mut experience), None);
+    }
+
+    #[test]
+    fn span_ignores_private_events() {
+        let mut private_event = experience_event("late", event("Late", 20, Value::Null));
+        private_event.private = true;
+        let mut experience = Experience {
+            events: HashMap::from([
+                (
+                    AvailablePlugins::timeline_plugin_experience,
+                    vec![experience_event("trip", event("Trip", 0, Value::Null))],
+                ),
+                (
+                    AvailablePlugins::timeline_plugin_media_scan,
+                    vec![
+                        experience_event("early", event("Early", 10, Value::Null)),
+                        private_event,
+                    ],
+                ),
+            ]),
+            public: true,
+            name: "Trip".to_string(),
+            tags: Vec::new(),
+            metadata: HashMap::new(),
+            parent: None,
+            children: Vec::new(),
+            connection_details: HashMap::new(),
+            owner: None,
+            acl: Vec::new(),
+            created_time: None,
+        };
+
+        let early = Timing::Instant(Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap());
+        assert_eq!(update_span("trip", &mut experience), Some(early));
+        assert_eq!(
+            compute_span(&experience, true),
+            Some(Timing::Range(TimeRange {
+                start: Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2024, 5, 1, 20, 0, 0).unwrap(),
+            }))
+        );
+    }
+
+    #[test]
+    fn refreshed_event_matches_identity_key() {
+        let stored = event("Photo", 10, json!({ "path": "a.jpg", "width": 100 }));
+        let candidates = [
+            event("Other photo", 10, json!({ "path": "b.jpg", "width": 100 })),
+            event("Renamed photo", 11, json!({ "path": "a.jpg", "width": 200 })),
+        ];
+        assert_eq!(find_refreshed_event(&stored, &candidates), Some(&candidates[1]));
+    }
+
+    #[test]
+    fn refreshed_event_ignores_other_events_at_the_same_time() {
+        let stored = event("Photo", 10, json!({ "path": "a.jpg" }));
+        let candidates = [event("Photo", 10, json!({ "path": "b.jpg" }))];
+        assert_eq!(find_refreshed_event(&stored, &candidates), None);
+
+        let stored = event("Song", 10, json!("song.mp3"));
+        let candidates = [event("Other song", 10, json!("other.mp3"))];
+        assert_eq!(find_refreshed_event(&stored, &candidates), None);
+    }
+
+    #[test]
+    fn refreshed_event_without_identity_falls_back_to_data_and_title() {
+        let stored = event("Song", 10, json!("song.mp3"));
+        let candidates = [
+            event("Other song", 10, json!("other.mp3")),
+            event("Song (remastered)", 12, json!("song.mp3")),
+        ];
+        assert_eq!(find_refreshed_event(&stored, &candidates), Some(&candidates[1]));
+
+        let stored = event("Walk", 10, json!({ "steps": 100 }));
+        let candidates = [event("Walk", 10, json!({ "steps": 120 }))];
+        assert_eq!(find_refreshed_event(&stored, &candidates), Some(&candidates[0]));
+
+        let candidates = [
+            event("Walk", 10, json!({ "steps": 120 })),
+            event("Walk", 10, json!({ "steps": 140 })),
+        ];
+        assert_eq!(find_refreshed_event(&stored, &candidates), None);
+    }
+}
@@ -0,0 +1,222 @@
+use {
+    crate::experience_manager::{experience_time, ExperienceManager},
+    shared::{
+        standalone_experience_types::types::ConnectionKind,
+        types::{Experience, ExperienceResult, ExperienceSubgraph, GraphEdge, GraphNode},
+    },
+    std::collections::{HashMap, HashSet, VecDeque},
+    timeline_types::available_plugins::AvailablePlugins,
+};
+
+pub struct ExperienceGraph {
+    nodes: HashMap<String, GraphNode>,
+    edges: Vec<GraphEdge>,
+    adjacency: HashMap<String, HashSet<String>>,
+}
+
+impl ExperienceGraph {
+    pub async fn load(
+        experience_manager: &ExperienceManager,
+        visible: impl Fn(&Experience) -> bool,
+    ) -> ExperienceResult<ExperienceGraph> {
+        let mut experiences = HashMap::new();
+        for id in experience_manager.list_experiences().await? {
+            let experience = match experience_manager.get_experience(&id).await {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("Skipping experience {} in graph: {}", id, e);
+                    continue;
+                }
+            };
+            if visible(&experience) {
+                experiences.insert(id, experience);
+            }
+        }
+
+        let mut edges = Vec::new();
+        let mut peers = HashSet::new();
+        for (id, experience) in experiences.iter() {
+            if let Some(connections) = experience
+                .events
+                .get(&AvailablePlugins::timeline_plugin_experience)
+            {
+                for connection in connections {
+                    if connection.id != *id
+                        && experiences.contains_key(&connection.id)
+                        && peers.insert(peer_pair(id, &connection.id))
+                    {
+                        edges.push(GraphEdge {
+                            from: id.clone(),
+                            to: connection.id.clone(),
+                            kind: ConnectionKind::Peer,
+                            details: experience
+                                .connection_details
+                                .get(&connection.id)
+                                .cloned()
+                                .unwrap_or_default(),
+                        });
+                    }
+                }
+            }
+            for child in experience.children.iter() {
+                if experiences.contains_key(child) {
+                    edges.push(GraphEdge {
+                        from: id.clone(),
+                        to: child.clone(),
+                        kind: ConnectionKind::Child,
+                        details: Default::default(),
+                    });
+                }
+            }
+        }
+
+        let mut adjacency: HashMap<String, HashSet<String>> = experiences
+            .keys()
+            .map(|id| (id.clone(), HashSet::new()))
+            .collect();
+        for edge in edges.iter() {
+            if let Some(v) = adjacency.get_mut(&edge.from) {
+                v.insert(edge.to.clone());
+            }
+            if let Some(v) = adjacency.get_mut(&edge.to) {
+                v.insert(edge.from.clone());
+            }
+        }
+
+        let nodes = experiences
+            .into_iter()
+            .map(|(id, experience)| {
+                (
+                    id.clone(),
+                    GraphNode {
+                        time: experience_time(&experience, &id),
+                        cover: format!("/api/experience/{}/cover/big", id),
+                        id,
+                        name: experience.name,
+                        public: experience.public,
+                    },
+                )
+            })
+            .collect();
+
+        Ok(ExperienceGraph {
+            nodes,
+            edges,
+            adjacency,
+        })
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.nodes.contains_key(id)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &GraphNode> {
+        self.nodes.values()
+    }
+
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    pub fn neighborhood(&self, id: &str, depth: usize) -> Option<ExperienceSubgraph> {
+        if !self.contains(id) {
+            return None;
+        }
+
+        let mut visited = HashSet::from([id.to_string()]);
+        let mut queue = VecDeque::from([(id.to_string(), 0)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance >= depth {
+                continue;
+            }
+            for neighbour in self.adjacency[&current].iter() {
+                if visited.insert(neighbour.clone()) {
+                    queue.push_back((neighbour.clone(), distance + 1));
+                }
+            }
+        }
+
+        Some(self.subgraph(&visited))
+    }
+
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<GraphNode>> {
+        if !self.contains(from) || !self.contains(to) {
+            return None;
+        }
+
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![self.nodes[current].clone()];
+                let mut step = current;
+                while let Some(v) = previous.get(step) {
+                    path.push(self.nodes[*v].clone());
+                    step = v;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for neighbour in self.adjacency[current].iter() {
+                if visited.insert(neighbour.as_str()) {
+                    previous.insert(neighbour.as_str(), current);
+                    queue.push_back(neighbour.as_str());
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn components(&self) -> Vec<Vec<GraphNode>> {
+        let mut visited = HashSet::new();
+        let mut components = Vec::new();
+        for id in self.nodes.keys() {
+            if !visited.insert(id.as_str()) {
+                continue;
+            }
+            let mut component = Vec::new();
+            let mut stack = vec![id.as_str()];
+            while let Some(current) = stack.pop() {
+                component.push(self.nodes[current].clone());
+                for neighbour in self.adjacency[current].iter() {
+                    if visited.insert(neighbour.as_str()) {
+                        stack.push(neighbour.as_str());
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components.sort_by_key(|v| std::cmp::Reverse(v.len()));
+        components
+    }
+
+    pub fn isolated(&self) -> Vec<GraphNode> {
+        self.adjacency
+            .iter()
+            .filter(|(_, neighbours)| neighbours.is_empty())
+            .map(|(id, _)| self.nodes[id].clone())
+            .collect()
+    }
+
+    fn subgraph(&self, ids: &HashSet<String>) -> ExperienceSubgraph {
+        ExperienceSubgraph {
+            nodes: ids.iter().map(|id| self.nodes[id].clone()).collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|v| ids.contains(&v.from) && ids.contains(&v.to))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+fn peer_pair(a: &str, b: &str) -> (String, String) {
+    if a < b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
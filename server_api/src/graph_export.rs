@@ -0,0 +1,325 @@
+use {
+    crate::{graph::ExperienceGraph, timing::timing_bounds},
+    serde::Serialize,
+    shared::{
+        standalone_experience_types::types::ConnectionKind,
+        types::{ExperienceResult, GraphEdge, GraphNode},
+    },
+    std::{
+        fmt::{self, Write},
+        str::FromStr,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphFormat {
+    Dot,
+    GraphMl,
+    Json,
+}
+
+impl GraphFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "text/vnd.graphviz",
+            GraphFormat::GraphMl => "application/graphml+xml",
+            GraphFormat::Json => "application/json",
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dot" | "gv" => Ok(GraphFormat::Dot),
+            "graphml" => Ok(GraphFormat::GraphMl),
+            "json" => Ok(GraphFormat::Json),
+            v => Err(format!("Unknown graph format: {}", v)),
+        }
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphFormat::Dot => write!(f, "dot"),
+            GraphFormat::GraphMl => write!(f, "graphml"),
+            GraphFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+pub fn export_graph(graph: &ExperienceGraph, format: GraphFormat) -> ExperienceResult<String> {
+    export(graph.nodes(), graph.edges(), format)
+}
+
+fn export<'a>(
+    nodes: impl Iterator<Item = &'a GraphNode>,
+    edges: &[GraphEdge],
+    format: GraphFormat,
+) -> ExperienceResult<String> {
+    let mut nodes = nodes.map(ExportNode::from).collect::<Vec<_>>();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+    let edges = edges.iter().map(ExportEdge::from).collect::<Vec<_>>();
+
+    Ok(match format {
+        GraphFormat::Dot => export_dot(&nodes, &edges),
+        GraphFormat::GraphMl => export_graphml(&nodes, &edges),
+        GraphFormat::Json => serde_json::to_string_pretty(&NodeLinkGraph {
+            directed: true,
+            multigraph: false,
+            nodes,
+            links: edges
+                .into_iter()
+                .flat_map(|v| {
+                    let reversed = (!v.directed).then(|| ExportEdge {
+                        source: v.target.clone(),
+                        target: v.source.clone(),
+                        ..v.clone()
+                    });
+                    [Some(v), reversed]
+                })
+                .flatten()
+                .collect(),
+        })?,
+    })
+}
+
+#[derive(Serialize)]
+struct NodeLinkGraph {
+    directed: bool,
+    multigraph: bool,
+    nodes: Vec<ExportNode>,
+    links: Vec<ExportEdge>,
+}
+
+#[derive(Serialize)]
+struct ExportNode {
+    id: String,
+    name: String,
+    public: bool,
+    start: Option<String>,
+    end: Option<String>,
+    cover: String,
+}
+
+impl From<&GraphNode> for ExportNode {
+    fn from(value: &GraphNode) -> Self {
+        let bounds = value.time.as_ref().map(timing_bounds);
+        ExportNode {
+            id: value.id.clone(),
+            name: value.name.clone(),
+            public: value.public,
+            start: bounds.map(|v| v.0.to_rfc3339()),
+            end: bounds.map(|v| v.1.to_rfc3339()),
+            cover: value.cover.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ExportEdge {
+    source: String,
+    target: String,
+    kind: String,
+    directed: bool,
+    relationship: String,
+    label: Option<String>,
+}
+
+impl From<&GraphEdge> for ExportEdge {
+    fn from(value: &GraphEdge) -> Self {
+        ExportEdge {
+            source: value.from.clone(),
+            target: value.to.clone(),
+            kind: match value.kind {
+                ConnectionKind::Peer => "peer",
+                ConnectionKind::Parent => "parent",
+                ConnectionKind::Child => "child",
+            }
+            .to_string(),
+            directed: value.kind != ConnectionKind::Peer,
+            relationship: value.details.relationship.name().to_string(),
+            label: value.details.label.clone(),
+        }
+    }
+}
+
+fn export_dot(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut dot = String::new();
+    let _ = writeln!(dot, "digraph experiences {{");
+    for node in nodes {
+        let _ = writeln!(
+            dot,
+            "  \"{}\" [label=\"{}\", public={}, start=\"{}\", end=\"{}\", cover=\"{}\"];",
+            escape_dot(&node.id),
+            escape_dot(&node.name),
+            node.public,
+            escape_dot(node.start.as_deref().unwrap_or_default()),
+            escape_dot(node.end.as_deref().unwrap_or_default()),
+            escape_dot(&node.cover)
+        );
+    }
+    for edge in edges {
+        let _ = writeln!(
+            dot,
+            "  \"{}\" -> \"{}\" [kind=\"{}\", relationship=\"{}\", label=\"{}\"{}];",
+            escape_dot(&edge.source),
+            escape_dot(&edge.target),
+            edge.kind,
+            escape_dot(&edge.relationship),
+            escape_dot(edge.label.as_deref().unwrap_or_default()),
+            if edge.directed { "" } else { ", dir=none" }
+        );
+    }
+    let _ = writeln!(dot, "}}");
+    dot
+}
+
+fn export_graphml(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut graphml = String::new();
+    let _ = writeln!(graphml, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    let _ = writeln!(
+        graphml,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    );
+    for (id, target, attr_type) in [
+        ("name", "node", "string"),
+        ("public", "node", "boolean"),
+        ("start", "node", "string"),
+        ("end", "node", "string"),
+        ("cover", "node", "string"),
+        ("kind", "edge", "string"),
+        ("relationship", "edge", "string"),
+        ("label", "edge", "string"),
+    ] {
+        let _ = writeln!(
+            graphml,
+            "  <key id=\"{0}\" for=\"{1}\" attr.name=\"{0}\" attr.type=\"{2}\"/>",
+            id, target, attr_type
+        );
+    }
+    let _ = writeln!(
+        graphml,
+        "  <graph id=\"experiences\" edgedefault=\"directed\">"
+    );
+    for node in nodes {
+        let _ = writeln!(graphml, "    <node id=\"{}\">", escape_xml(&node.id));
+        for (key, value) in [
+            ("name", escape_xml(&node.name)),
+            ("public", node.public.to_string()),
+            (
+                "start",
+                escape_xml(node.start.as_deref().unwrap_or_default()),
+            ),
+            ("end", escape_xml(node.end.as_deref().unwrap_or_default())),
+            ("cover", escape_xml(&node.cover)),
+        ] {
+            let _ = writeln!(graphml, "      <data key=\"{}\">{}</data>", key, value);
+        }
+        let _ = writeln!(graphml, "    </node>");
+    }
+    for edge in edges {
+        let _ = writeln!(
+            graphml,
+            "    <edge source=\"{}\" target=\"{}\" directed=\"{}\">",
+            escape_xml(&edge.source),
+            escape_xml(&edge.target),
+            edge.directed
+        );
+        for (key, value) in [
+            ("kind", edge.kind.clone()),
+            ("relationship", escape_xml(&edge.relationship)),
+            (
+                "label",
+                escape_xml(edge.label.as_deref().unwrap_or_default()),
+            ),
+        ] {
+            let _ = writeln!(graphml, "      <data key=\"{}\">{}</data>", key, value);
+        }
+        let _ = writeln!(graphml, "    </edge>");
+    }
+    let _ = writeln!(graphml, "  </graph>");
+    let _ = writeln!(graphml, "</graphml>");
+    graphml
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::Value,
+        shared::types::{ConnectionDetails, ConnectionRelationship},
+    };
+
+    fn node(id: &str) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            name: id.to_string(),
+            public: true,
+            time: None,
+            cover: String::new(),
+        }
+    }
+
+    fn edge(from: &str, to: &str, kind: ConnectionKind) -> GraphEdge {
+        GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+            details: ConnectionDetails {
+                relationship: ConnectionRelationship::SamePlace,
+                label: Some("label".to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn json_links_peers_both_ways() {
+        let nodes = [node("a"), node("b"), node("c")];
+        let edges = [
+            edge("a", "b", ConnectionKind::Peer),
+            edge("a", "c", ConnectionKind::Child),
+        ];
+        let graph: Value =
+            serde_json::from_str(&export(nodes.iter(), &edges, GraphFormat::Json).unwrap())
+                .unwrap();
+
+        assert_eq!(graph["directed"], true);
+        let links = graph["links"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| {
+                (
+                    v["source"].as_str().unwrap(),
+                    v["target"].as_str().unwrap(),
+                    v["kind"].as_str().unwrap(),
+                    v["label"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            links,
+            [
+                ("a", "b", "peer", "label"),
+                ("b", "a", "peer", "label"),
+                ("a", "c", "child", "label"),
+            ]
+        );
+    }
+}
@@ -1,7 +1,27 @@
 #![feature(let_chains)]
 
+pub mod access;
+pub mod audit;
+pub mod backup;
 pub mod config;
 pub mod experience_manager;
+pub mod graph;
+pub mod graph_export;
+pub mod memories;
 pub mod plugin;
+pub mod positions;
+pub mod rate_limit;
+pub mod refresh;
 pub mod render;
 pub mod renderer;
+pub mod shares;
+pub mod site_export;
+pub mod suggestions;
+pub mod timeline;
+pub mod timing;
+pub mod tokens;
+pub mod users;
+pub mod webhooks;
+
+#[cfg(test)]
+mod mock_server;
@@ -0,0 +1,83 @@
+use {
+    crate::{
+        access::AccessLevel,
+        experience_manager::{experience_time, ExperienceManager},
+        timing::timing_bounds,
+    },
+    chrono::{Datelike, NaiveDate},
+    shared::types::{EventMemory, Experience, ExperienceMemory, ExperienceResult, Memories},
+    timeline_types::{available_plugins::AvailablePlugins, timing::Timing},
+};
+
+pub async fn on_this_day(
+    experience_manager: &ExperienceManager,
+    date: NaiveDate,
+    access: impl Fn(&Experience) -> AccessLevel,
+) -> ExperienceResult<Memories> {
+    let mut experiences = Vec::new();
+    let mut events = Vec::new();
+
+    for id in experience_manager.list_experiences().await? {
+        let experience = experience_manager.get_experience(&id).await?;
+        let level = access(&experience);
+        if level < AccessLevel::Viewer {
+            continue;
+        }
+
+        if let Some(time) = experience_time(&experience, &id)
+            && let Some(years_ago) = years_ago(&time, date)
+        {
+            experiences.push(ExperienceMemory {
+                cover: format!("/api/experience/{}/cover/big", id),
+                id: id.clone(),
+                name: experience.name.clone(),
+                time,
+                years_ago,
+            });
+        }
+
+        for (plugin, plugin_events) in experience.events.iter() {
+            if *plugin == AvailablePlugins::timeline_plugin_experience {
+                continue;
+            }
+            for event in plugin_events
+                .iter()
+                .filter(|v| v.favorite && (!v.private || level == AccessLevel::Owner))
+            {
+                if let Some(years_ago) = years_ago(&event.event.time, date) {
+                    events.push(EventMemory {
+                        cover: format!("/api/experience/{}/event/{}/cover", id, event.id),
+                        experience_id: id.clone(),
+                        experience_name: experience.name.clone(),
+                        event_id: event.id.clone(),
+                        plugin: plugin.clone(),
+                        title: event.event.title.clone(),
+                        time: event.event.time.clone(),
+                        years_ago,
+                    });
+                }
+            }
+        }
+    }
+
+    experiences.sort_by(|a, b| a.time.cmp(&b.time));
+    events.sort_by(|a, b| a.time.cmp(&b.time));
+
+    Ok(Memories {
+        date: date.format("%Y-%m-%d").to_string(),
+        experiences,
+        events,
+    })
+}
+
+fn years_ago(time: &Timing, date: NaiveDate) -> Option<i32> {
+    let (start, end) = timing_bounds(time);
+    let (start, end) = (start.date_naive(), end.date_naive());
+    (start.year()..=end.year().min(date.year() - 1))
+        .rev()
+        .find(|year| {
+            NaiveDate::from_ymd_opt(*year, date.month(), date.day())
+                .is_some_and(|v| start <= v && v <= end)
+        })
+        .map(|year| date.year() - year)
+}
@@ -0,0 +1,70 @@
+use {
+    std::collections::HashMap,
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    },
+    url::Url,
+};
+
+pub struct ReceivedRequest {
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+pub async fn serve_once(response: String) -> (Url, JoinHandle<ReceivedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = Vec::new();
+        let mut buffer = [0; 4096];
+        let (head, body_start) = loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            received.extend_from_slice(&buffer[..read]);
+            if let Some(v) = received.windows(4).position(|v| v == b"\r\n\r\n") {
+                break (String::from_utf8_lossy(&received[..v]).to_string(), v + 4);
+            }
+        };
+
+        let mut lines = head.lines();
+        let path = lines
+            .next()
+            .and_then(|v| v.split_whitespace().nth(1))
+            .unwrap_or_default()
+            .to_string();
+        let headers = lines
+            .filter_map(|v| v.split_once(':'))
+            .map(|(key, value)| (key.trim().to_lowercase(), value.trim().to_string()))
+            .collect::<HashMap<_, _>>();
+        let length = headers
+            .get("content-length")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        while received.len() < body_start + length {
+            let read = stream.read(&mut buffer).await.unwrap();
+            received.extend_from_slice(&buffer[..read]);
+        }
+
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        ReceivedRequest {
+            path,
+            headers,
+            body: String::from_utf8_lossy(&received[body_start..body_start + length]).to_string(),
+        }
+    });
+    (url, handle)
+}
@@ -0,0 +1,61 @@
+use {
+    crate::config::Config,
+    shared::types::{ExperienceError, ExperienceResult},
+    std::{collections::HashMap, io::ErrorKind, path::PathBuf},
+    tokio::{
+        fs::{read_to_string, rename, write},
+        sync::RwLock,
+    },
+};
+
+pub struct PositionManager {
+    positions_file: PathBuf,
+    positions: RwLock<HashMap<String, String>>,
+}
+
+impl PositionManager {
+    pub async fn load(config: &Config) -> ExperienceResult<PositionManager> {
+        let positions = match read_to_string(&config.positions_file).await {
+            Ok(v) => serde_json::from_str(&v)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+        };
+
+        Ok(PositionManager {
+            positions_file: config.positions_file.clone(),
+            positions: RwLock::new(positions),
+        })
+    }
+
+    pub async fn get_position(&self, user: &str) -> Option<String> {
+        self.positions.read().await.get(user).cloned()
+    }
+
+    pub async fn set_position(&self, user: &str, experience_id: &str) -> ExperienceResult<bool> {
+        let mut positions = self.positions.write().await;
+        if positions.get(user).is_some_and(|v| v == experience_id) {
+            return Ok(false);
+        }
+        positions.insert(user.to_string(), experience_id.to_string());
+        self.save(&positions).await?;
+        Ok(true)
+    }
+
+    pub async fn remove_position(&self, user: &str) -> ExperienceResult<()> {
+        let mut positions = self.positions.write().await;
+        if positions.remove(user).is_some() {
+            self.save(&positions).await?;
+        }
+        Ok(())
+    }
+
+    async fn save(&self, positions: &HashMap<String, String>) -> ExperienceResult<()> {
+        let temp = self.positions_file.with_extension("json.tmp");
+        if let Err(e) = write(&temp, serde_json::to_string_pretty(positions)?).await {
+            return Err(ExperienceError::UnableToWrite(e.to_string()));
+        }
+        rename(&temp, &self.positions_file)
+            .await
+            .map_err(|e| ExperienceError::UnableToWrite(e.to_string()))
+    }
+}
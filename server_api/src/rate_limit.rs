@@ -0,0 +1,187 @@
+use {
+    crate::config::RateLimitConfig,
+    std::{
+        collections::HashMap,
+        net::IpAddr,
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+};
+
+#[derive(Default)]
+struct Failures {
+    window_start: Option<Instant>,
+    count: u32,
+    lockouts: u32,
+    locked_until: Option<Instant>,
+    last_failure: Option<Instant>,
+}
+
+impl Failures {
+    fn locked(&self, now: Instant) -> Option<Duration> {
+        self.locked_until.filter(|v| *v > now).map(|v| v - now)
+    }
+
+    fn record(&mut self, now: Instant, limit: u32, config: &RateLimitConfig) -> Option<Duration> {
+        let window = Duration::from_secs(config.window_seconds);
+        if self.window_start.is_none_or(|v| now - v > window) {
+            self.window_start = Some(now);
+            self.count = 0;
+        }
+        self.count += 1;
+        self.last_failure = Some(now);
+        if self.count < limit {
+            return None;
+        }
+
+        let lockout = Duration::from_secs(config.lockout_seconds)
+            .saturating_mul(2u32.saturating_pow(self.lockouts))
+            .min(Duration::from_secs(config.max_lockout_seconds));
+        self.lockouts += 1;
+        self.count = 0;
+        self.window_start = None;
+        self.locked_until = Some(now + lockout);
+        Some(lockout)
+    }
+}
+
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    ips: Mutex<HashMap<IpAddr, Failures>>,
+    global: Mutex<Failures>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> RateLimiter {
+        RateLimiter {
+            config,
+            ips: Mutex::new(HashMap::new()),
+            global: Mutex::new(Failures::default()),
+        }
+    }
+
+    pub fn check(&self, ip: Option<IpAddr>) -> Result<(), Duration> {
+        let now = Instant::now();
+        let remaining = match ip {
+            Some(ip) => match self.ips.lock().unwrap().get(&ip) {
+                Some(failures) => failures
+                    .locked(now)
+                    .or_else(|| self.global.lock().unwrap().locked(now)),
+                None => None,
+            },
+            None => self.global.lock().unwrap().locked(now),
+        };
+        match remaining {
+            Some(remaining) => {
+                eprintln!(
+                    "Rejected authentication attempt from {} during lockout, {}s remaining",
+                    ip.map(|v| v.to_string()).unwrap_or("unknown client".to_string()),
+                    remaining.as_secs()
+                );
+                Err(remaining)
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn record_failure(&self, ip: Option<IpAddr>) {
+        let now = Instant::now();
+        if let Some(ip) = ip {
+            let mut ips = self.ips.lock().unwrap();
+            let max_lockout = Duration::from_secs(self.config.max_lockout_seconds);
+            ips.retain(|_, v| v.last_failure.is_some_and(|v| now - v < max_lockout));
+            if let Some(lockout) =
+                ips.entry(ip)
+                    .or_default()
+                    .record(now, self.config.ip_failures, &self.config)
+            {
+                eprintln!(
+                    "Too many failed authentication attempts from {}, locked out for {}s",
+                    ip,
+                    lockout.as_secs()
+                );
+            }
+        }
+
+        if let Some(lockout) =
+            self.global
+                .lock()
+                .unwrap()
+                .record(now, self.config.global_failures, &self.config)
+        {
+            eprintln!(
+                "Too many failed authentication attempts, locked out all clients for {}s",
+                lockout.as_secs()
+            );
+        }
+    }
+
+    pub fn record_success(&self, ip: Option<IpAddr>) {
+        if let Some(ip) = ip {
+            self.ips.lock().unwrap().remove(&ip);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RateLimitConfig {
+        RateLimitConfig {
+            ip_failures: 3,
+            global_failures: 5,
+            window_seconds: 60,
+            lockout_seconds: 30,
+            max_lockout_seconds: 100,
+        }
+    }
+
+    #[test]
+    fn record_locks_out_after_limit() {
+        let config = config();
+        let now = Instant::now();
+        let mut failures = Failures::default();
+        assert_eq!(failures.record(now, 3, &config), None);
+        assert_eq!(failures.record(now, 3, &config), None);
+        assert_eq!(failures.record(now, 3, &config), Some(Duration::from_secs(30)));
+        assert_eq!(failures.locked(now), Some(Duration::from_secs(30)));
+        assert_eq!(failures.locked(now + Duration::from_secs(30)), None);
+    }
+
+    #[test]
+    fn record_resets_count_after_window() {
+        let config = config();
+        let now = Instant::now();
+        let mut failures = Failures::default();
+        failures.record(now, 3, &config);
+        failures.record(now, 3, &config);
+        let later = now + Duration::from_secs(61);
+        assert_eq!(failures.record(later, 3, &config), None);
+        assert_eq!(failures.count, 1);
+    }
+
+    #[test]
+    fn record_doubles_lockouts_up_to_max() {
+        let config = config();
+        let now = Instant::now();
+        let mut failures = Failures::default();
+        let lockouts = (0..3)
+            .map(|_| (0..3).find_map(|_| failures.record(now, 3, &config)).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lockouts, [30, 60, 100].map(Duration::from_secs));
+    }
+
+    #[test]
+    fn global_lockout_spares_clients_without_failures() {
+        let limiter = RateLimiter::new(config());
+        let attacker: IpAddr = [10, 0, 0, 1].into();
+        let owner: IpAddr = [10, 0, 0, 2].into();
+        for _ in 0..5 {
+            limiter.record_failure(Some(attacker));
+        }
+        assert!(limiter.check(Some(attacker)).is_err());
+        assert!(limiter.check(None).is_err());
+        assert!(limiter.check(Some(owner)).is_ok());
+    }
+}
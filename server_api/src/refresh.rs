@@ -0,0 +1,71 @@
+use {
+    crate::{
+        audit::{entry, AuditLog, SYSTEM_ACTOR},
+        experience_manager::ExperienceManager,
+        timeline::TimelineClient,
+    },
+    shared::types::{AuditAction, ExperienceResult, RefreshReport},
+    std::{sync::Arc, time::Duration},
+};
+
+pub async fn refresh_all(
+    experience_manager: &ExperienceManager,
+    timeline: &TimelineClient,
+    audit: &AuditLog,
+) -> ExperienceResult<Vec<RefreshReport>> {
+    let mut reports = Vec::new();
+    for id in experience_manager.list_experiences().await? {
+        let report = match experience_manager.refresh_events(&id, timeline, true).await {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Unable to refresh experience {}: {}", id, e);
+                continue;
+            }
+        };
+        if !report.changed.is_empty() {
+            audit_refresh(audit, &report).await;
+        }
+        if !report.changed.is_empty() || !report.missing.is_empty() {
+            reports.push(report);
+        }
+    }
+    Ok(reports)
+}
+
+pub async fn audit_refresh(audit: &AuditLog, report: &RefreshReport) {
+    audit
+        .record(&entry(
+            AuditAction::Refresh,
+            Some(SYSTEM_ACTOR.to_string()),
+            None,
+            Some(&report.experience_id),
+            Some(format!("{} events changed", report.changed.len())),
+        ))
+        .await;
+}
+
+pub async fn schedule(
+    experience_manager: ExperienceManager,
+    timeline: TimelineClient,
+    audit: Arc<AuditLog>,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match refresh_all(&experience_manager, &timeline, &audit).await {
+            Ok(reports) => {
+                for report in reports {
+                    eprintln!(
+                        "Refreshed experience {}: {} events changed, {} events missing in timeline",
+                        report.experience_id,
+                        report.changed.len(),
+                        report.missing.len()
+                    );
+                }
+            }
+            Err(e) => eprintln!("Unable to refresh experiences from timeline: {}", e),
+        }
+    }
+}
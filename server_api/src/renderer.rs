@@ -41,7 +41,7 @@ impl Renderer {
         let mut favorites = Vec::new();
         for (plugin, events) in experience.events.iter() {
             for event in events.iter() {
-                if event.favorite {
+                if event.favorite && !event.private {
                     favorites.push((plugin.clone(), &event.event))
                 }
             }
@@ -0,0 +1,139 @@
+use {
+    crate::config::Config,
+    chrono::{DateTime, Duration, Utc},
+    serde::{Deserialize, Serialize},
+    shared::types::{ExperienceError, ExperienceResult, ShareToken},
+    std::{collections::HashMap, io::ErrorKind, path::PathBuf},
+    tokio::{
+        fs::{read_to_string, rename, write},
+        sync::RwLock,
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Share {
+    experience_id: String,
+    #[serde(flatten)]
+    token: ShareToken,
+}
+
+impl Share {
+    fn expired(&self) -> bool {
+        self.token.expires.is_some_and(|v| v <= Utc::now())
+    }
+
+    fn exhausted(&self) -> bool {
+        self.token.max_views.is_some_and(|v| self.token.views >= v)
+    }
+}
+
+pub struct ShareManager {
+    shares_file: PathBuf,
+    shares: RwLock<HashMap<String, Share>>,
+}
+
+impl ShareManager {
+    pub async fn load(config: &Config) -> ExperienceResult<ShareManager> {
+        let shares = match read_to_string(&config.shares_file).await {
+            Ok(v) => serde_json::from_str::<Vec<Share>>(&v)?
+                .into_iter()
+                .map(|v| (v.token.token.clone(), v))
+                .collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+        };
+
+        Ok(ShareManager {
+            shares_file: config.shares_file.clone(),
+            shares: RwLock::new(shares),
+        })
+    }
+
+    pub async fn get_shares(&self, experience_id: &str) -> Vec<ShareToken> {
+        let mut shares = self
+            .shares
+            .read()
+            .await
+            .values()
+            .filter(|v| v.experience_id == experience_id)
+            .map(|v| v.token.clone())
+            .collect::<Vec<_>>();
+        shares.sort_by_key(|v| v.created);
+        shares
+    }
+
+    pub async fn create_share(
+        &self,
+        experience_id: &str,
+        expires_in_hours: Option<u32>,
+        max_views: Option<u32>,
+    ) -> ExperienceResult<ShareToken> {
+        let created = Utc::now();
+        let token = ShareToken {
+            token: uuid::Uuid::new_v4().simple().to_string(),
+            created,
+            expires: expires_in_hours.map(|v| created + Duration::hours(v as i64)),
+            max_views,
+            views: 0,
+        };
+
+        let mut shares = self.shares.write().await;
+        shares.insert(
+            token.token.clone(),
+            Share {
+                experience_id: experience_id.to_string(),
+                token: token.clone(),
+            },
+        );
+        self.save(&shares).await?;
+        Ok(token)
+    }
+
+    pub async fn revoke_share(&self, experience_id: &str, token: &str) -> ExperienceResult<()> {
+        let mut shares = self.shares.write().await;
+        if !shares
+            .get(token)
+            .is_some_and(|v| v.experience_id == experience_id)
+        {
+            return Err(ExperienceError::NotFound(token.to_string()));
+        }
+        shares.remove(token);
+        self.save(&shares).await
+    }
+
+    pub async fn redeem(
+        &self,
+        token: &str,
+    ) -> ExperienceResult<(String, Option<DateTime<Utc>>)> {
+        let mut shares = self.shares.write().await;
+        let share = match shares.get_mut(token) {
+            Some(v) if !v.expired() && !v.exhausted() => v,
+            _ => return Err(ExperienceError::NotFound(token.to_string())),
+        };
+        share.token.views += 1;
+        let redeemed = (share.experience_id.clone(), share.token.expires);
+        self.save(&shares).await?;
+        Ok(redeemed)
+    }
+
+    pub async fn shared_experience(&self, token: &str) -> Option<String> {
+        self.shares
+            .read()
+            .await
+            .get(token)
+            .filter(|v| !v.expired())
+            .map(|v| v.experience_id.clone())
+    }
+
+    async fn save(&self, shares: &HashMap<String, Share>) -> ExperienceResult<()> {
+        let mut shares = shares.values().collect::<Vec<_>>();
+        shares.sort_by_key(|v| v.token.created);
+        let temp = self.shares_file.with_extension("json.tmp");
+        if let Err(e) = write(&temp, serde_json::to_string_pretty(&shares)?).await {
+            return Err(ExperienceError::UnableToWrite(e.to_string()));
+        }
+        rename(&temp, &self.shares_file)
+            .await
+            .map_err(|e| ExperienceError::UnableToWrite(e.to_string()))
+    }
+}
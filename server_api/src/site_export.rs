@@ -0,0 +1,280 @@
+use {
+    crate::{experience_manager::ExperienceManager, timing::format_timing},
+    shared::types::{Experience, ExperienceError, ExperienceResult},
+    std::path::Path,
+    timeline_types::available_plugins::AvailablePlugins,
+    tokio::fs::{copy, create_dir_all, write},
+};
+
+const EVENT_IMAGE_SIZE: i32 = 300;
+
+const STYLE: &str = r#"
+:root {
+  --darkColor: #292f36;
+  --lightColor: #fcfafa;
+  --accentColor1: #2589bd;
+  --accentColor1Light: #84bbd8;
+  --accentColor3: #8fb339;
+  --accentColor3Light: #bed883;
+  --contentSpacing: 12px;
+}
+
+body {
+  font-family: sans-serif;
+  margin: 0;
+  background-color: var(--accentColor1);
+  color: var(--lightColor);
+}
+
+a {
+  color: inherit;
+}
+
+.titleBar {
+  background-color: var(--darkColor);
+  padding: calc(var(--contentSpacing) * 3.5);
+  text-align: center;
+}
+
+.band {
+  background-color: var(--accentColor3);
+  padding: var(--contentSpacing);
+  text-align: center;
+}
+
+.cards {
+  display: flex;
+  flex-wrap: wrap;
+  gap: var(--contentSpacing);
+  padding: var(--contentSpacing);
+  background-color: var(--accentColor3Light);
+}
+
+.card {
+  width: 100px;
+  border: 3px solid var(--accentColor3);
+  border-radius: 5px;
+  background-color: var(--accentColor3);
+  text-align: center;
+  text-decoration: none;
+  word-wrap: break-word;
+}
+
+.card img {
+  width: 100%;
+  border-radius: 5px;
+}
+
+.cover {
+  display: block;
+  max-width: 500px;
+  width: 100%;
+  margin: auto;
+}
+
+.events {
+  display: flex;
+  flex-direction: column;
+  gap: var(--contentSpacing);
+  padding: var(--contentSpacing);
+}
+
+.event {
+  background-color: var(--darkColor);
+  padding: var(--contentSpacing);
+}
+
+.event img {
+  display: block;
+  max-width: 100%;
+}
+"#;
+
+pub async fn export_site(
+    experience_manager: &ExperienceManager,
+    output: &Path,
+) -> ExperienceResult<usize> {
+    let mut experiences = Vec::new();
+    for id in experience_manager.list_experiences().await? {
+        let experience = experience_manager.get_experience(&id).await?;
+        if experience.public {
+            experiences.push((id, experience));
+        }
+    }
+    experiences.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+
+    create_output_dir(&output.join("covers")).await?;
+    create_output_dir(&output.join("experiences")).await?;
+    write_output(&output.join("style.css"), STYLE).await?;
+
+    for (id, _) in experiences.iter() {
+        for small in [false, true] {
+            let cover = experience_manager.cover_path(id, small);
+            if let Some(file_name) = cover.file_name()
+                && let Err(e) = copy(&cover, output.join("covers").join(file_name)).await
+            {
+                eprintln!("Unable to copy cover of experience {}: {}", id, e);
+            }
+        }
+    }
+
+    let cards = experiences
+        .iter()
+        .map(|(id, experience)| {
+            render_card(
+                &format!("experiences/{}.html", id),
+                &format!("covers/{}.small.png", id),
+                &experience.name,
+            )
+        })
+        .collect::<String>();
+    write_output(
+        &output.join("index.html"),
+        &render_page(
+            "Experiences",
+            "style.css",
+            &format!("<div class=\"cards\">{}</div>", cards),
+        ),
+    )
+    .await?;
+
+    for (id, experience) in experiences.iter() {
+        let events_folder = output.join("experiences").join(id);
+        create_output_dir(&events_folder).await?;
+        let page = render_experience(
+            experience_manager,
+            id,
+            experience,
+            &experiences,
+            &events_folder,
+        )
+        .await;
+        write_output(
+            &output.join("experiences").join(format!("{}.html", id)),
+            &page,
+        )
+        .await?;
+    }
+
+    Ok(experiences.len())
+}
+
+async fn render_experience(
+    experience_manager: &ExperienceManager,
+    id: &str,
+    experience: &Experience,
+    public_experiences: &[(String, Experience)],
+    events_folder: &Path,
+) -> String {
+    let connections = experience
+        .events
+        .get(&AvailablePlugins::timeline_plugin_experience)
+        .map(|v| {
+            v.iter()
+                .filter(|v| v.id != id)
+                .filter_map(|v| public_experiences.iter().find(|(id, _)| *id == v.id))
+                .map(|(id, experience)| {
+                    render_card(
+                        &format!("{}.html", id),
+                        &format!("../covers/{}.small.png", id),
+                        &experience.name,
+                    )
+                })
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+
+    let mut events = experience
+        .events
+        .iter()
+        .filter(|(plugin, _)| **plugin != AvailablePlugins::timeline_plugin_experience)
+        .flat_map(|(plugin, events)| events.iter().map(move |event| (plugin, event)))
+        .filter(|(_, event)| !event.private)
+        .collect::<Vec<_>>();
+    events.sort_by(|a, b| a.1.event.time.cmp(&b.1.event.time));
+
+    let mut rendered_events = String::new();
+    for (plugin, event) in events {
+        let image_name = format!("{}.png", event.id);
+        let image = experience_manager
+            .renderer
+            .render_event(plugin, &event.event, (EVENT_IMAGE_SIZE, EVENT_IMAGE_SIZE))
+            .await;
+        let image = match image.write_png(events_folder.join(&image_name)) {
+            Ok(_) => format!("<img src=\"{}/{}\" />", escape(id), escape(&image_name)),
+            Err(e) => {
+                eprintln!("Unable to save image of event {}: {}", event.id, e);
+                String::new()
+            }
+        };
+        rendered_events.push_str(&format!(
+            "<div class=\"event\"><h3>{}{}</h3><div>{}</div>{}</div>",
+            if event.favorite { "&#9733; " } else { "" },
+            escape(&event.event.title),
+            escape(&format_timing(&event.event.time)),
+            image
+        ));
+    }
+
+    let content = format!(
+        "<a class=\"band\" href=\"../index.html\" style=\"display: block\">All experiences</a>\
+        <img class=\"cover\" src=\"../covers/{}.png\" />\
+        <div class=\"cards\">{}</div>\
+        <div class=\"events\">{}</div>",
+        escape(id),
+        connections,
+        rendered_events
+    );
+
+    render_page(&experience.name, "../style.css", &content)
+}
+
+fn render_card(href: &str, cover: &str, name: &str) -> String {
+    format!(
+        "<a class=\"card\" href=\"{}\"><img src=\"{}\" /><div>{}</div></a>",
+        escape(href),
+        escape(cover),
+        escape(name)
+    )
+}
+
+fn render_page(title: &str, style: &str, content: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\
+        <html lang=\"en\">\
+        <head>\
+        <meta charset=\"UTF-8\" />\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\" />\
+        <title>{0}</title>\
+        <link rel=\"stylesheet\" href=\"{1}\" />\
+        </head>\
+        <body>\
+        <div class=\"titleBar\"><h1>{0}</h1></div>\
+        {2}\
+        </body>\
+        </html>",
+        escape(title),
+        escape(style),
+        content
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+async fn create_output_dir(path: &Path) -> ExperienceResult<()> {
+    create_dir_all(path)
+        .await
+        .map_err(|e| ExperienceError::UnableToWrite(e.to_string()))
+}
+
+async fn write_output(path: &Path, content: &str) -> ExperienceResult<()> {
+    write(path, content)
+        .await
+        .map_err(|e| ExperienceError::UnableToWrite(e.to_string()))
+}
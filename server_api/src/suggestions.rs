@@ -0,0 +1,280 @@
+use {
+    crate::{
+        timeline::TimelineClient,
+        timing::{timing_bounds, timing_from_bounds},
+    },
+    chrono::{DateTime, Duration, Utc},
+    serde_json::Value,
+    shared::types::{ExperienceResult, ExperienceSuggestion, SuggestedEvent, SuggestionsRequest},
+    std::collections::HashMap,
+    timeline_types::{api::CompressedEvent, available_plugins::AvailablePlugins},
+};
+
+const DEFAULT_GAP_HOURS: u32 = 6;
+const DEFAULT_MIN_EVENTS: usize = 3;
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+pub async fn suggest(
+    timeline: &TimelineClient,
+    request: &SuggestionsRequest,
+) -> ExperienceResult<Vec<ExperienceSuggestion>> {
+    let events = timeline.get_events(&request.range).await?;
+    Ok(cluster_events(
+        events,
+        Duration::hours(request.gap_hours.unwrap_or(DEFAULT_GAP_HOURS) as i64),
+        request.max_distance_km,
+        request.min_events.unwrap_or(DEFAULT_MIN_EVENTS),
+    ))
+}
+
+struct Cluster {
+    events: Vec<SuggestedEvent>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    location: Option<(f64, f64)>,
+}
+
+pub fn cluster_events(
+    events: HashMap<AvailablePlugins, Vec<CompressedEvent>>,
+    gap: Duration,
+    max_distance_km: Option<f64>,
+    min_events: usize,
+) -> Vec<ExperienceSuggestion> {
+    let events = events
+        .into_iter()
+        .filter(|(plugin, _)| *plugin != AvailablePlugins::timeline_plugin_experience)
+        .flat_map(|(plugin, events)| {
+            events.into_iter().map(move |event| SuggestedEvent {
+                plugin: plugin.clone(),
+                event,
+            })
+        })
+        .collect::<Vec<_>>();
+    cluster(events, gap, max_distance_km, min_events)
+}
+
+fn cluster(
+    mut events: Vec<SuggestedEvent>,
+    gap: Duration,
+    max_distance_km: Option<f64>,
+    min_events: usize,
+) -> Vec<ExperienceSuggestion> {
+    events.sort_by(|a, b| a.event.time.cmp(&b.event.time));
+
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for event in events {
+        let (start, end) = timing_bounds(&event.event.time);
+        let location = location(&event.event.data);
+        let cluster = match clusters.last_mut() {
+            Some(cluster)
+                if start - cluster.end <= gap
+                    && !too_far(cluster.location, location, max_distance_km) =>
+            {
+                cluster
+            }
+            _ => {
+                clusters.push(Cluster {
+                    events: Vec::new(),
+                    start,
+                    end,
+                    location: None,
+                });
+                clusters.last_mut().unwrap()
+            }
+        };
+        cluster.end = cluster.end.max(end);
+        if location.is_some() {
+            cluster.location = location;
+        }
+        cluster.events.push(event);
+    }
+
+    clusters
+        .into_iter()
+        .filter(|v| v.events.len() >= min_events)
+        .map(|v| ExperienceSuggestion {
+            name: suggestion_name(v.start, v.end),
+            time: timing_from_bounds(v.start, v.end),
+            events: v.events,
+        })
+        .collect()
+}
+
+fn suggestion_name(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    if start.date_naive() == end.date_naive() {
+        start.format("%A, %d %B %Y").to_string()
+    } else {
+        format!("{} - {}", start.format("%d %B"), end.format("%d %B %Y"))
+    }
+}
+
+fn too_far(a: Option<(f64, f64)>, b: Option<(f64, f64)>, max_distance_km: Option<f64>) -> bool {
+    match (a, b, max_distance_km) {
+        (Some(a), Some(b), Some(max_distance_km)) => distance_km(a, b) > max_distance_km,
+        _ => false,
+    }
+}
+
+fn location(data: &Value) -> Option<(f64, f64)> {
+    [Some(data), data.get("location"), data.get("position")]
+        .into_iter()
+        .flatten()
+        .find_map(|v| {
+            let latitude = v.get("latitude").or_else(|| v.get("lat"))?.as_f64()?;
+            let longitude = v
+                .get("longitude")
+                .or_else(|| v.get("lon"))
+                .or_else(|| v.get("lng"))?
+                .as_f64()?;
+            Some((latitude, longitude))
+        })
+}
+
+fn distance_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat_a, lat_b) = (a.0.to_radians(), b.0.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.1 - a.1).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{config::Config, mock_server::serve_once},
+        chrono::TimeZone,
+        serde_json::json,
+        timeline_types::{
+            api::APIError,
+            timing::{TimeRange, Timing},
+        },
+    };
+
+    fn event(hour: u32, data: Value) -> SuggestedEvent {
+        SuggestedEvent {
+            plugin: AvailablePlugins::timeline_plugin_experience,
+            event: CompressedEvent {
+                title: format!("event at {}", hour),
+                time: Timing::Instant(Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()
+                    + Duration::hours(hour as i64)),
+                data,
+            },
+        }
+    }
+
+    fn titles(suggestion: &ExperienceSuggestion) -> Vec<String> {
+        suggestion.events.iter().map(|v| v.event.title.clone()).collect()
+    }
+
+    #[test]
+    fn clusters_by_gap_and_drops_small_clusters() {
+        let events = vec![
+            event(0, Value::Null),
+            event(2, Value::Null),
+            event(4, Value::Null),
+            event(20, Value::Null),
+            event(40, Value::Null),
+            event(41, Value::Null),
+        ];
+        let suggestions = cluster(events, Duration::hours(6), None, 2);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(titles(&suggestions[0]), ["event at 0", "event at 2", "event at 4"]);
+        assert_eq!(titles(&suggestions[1]), ["event at 40", "event at 41"]);
+        assert_eq!(suggestions[0].name, "Wednesday, 01 May 2024");
+    }
+
+    #[test]
+    fn splits_clusters_by_distance() {
+        let berlin = json!({ "latitude": 52.52, "longitude": 13.40 });
+        let potsdam = json!({ "location": { "lat": 52.39, "lng": 13.06 } });
+        let munich = json!({ "position": { "lat": 48.14, "lon": 11.58 } });
+        let events = vec![
+            event(0, berlin.clone()),
+            event(1, potsdam),
+            event(2, Value::Null),
+            event(3, munich),
+            event(4, berlin),
+        ];
+
+        let suggestions = cluster(events.clone(), Duration::hours(6), Some(50.0), 1);
+        assert_eq!(
+            suggestions.iter().map(titles).collect::<Vec<_>>(),
+            [
+                vec!["event at 0", "event at 1", "event at 2"],
+                vec!["event at 3"],
+                vec!["event at 4"],
+            ]
+        );
+        assert_eq!(cluster(events, Duration::hours(6), None, 1).len(), 1);
+    }
+
+    #[test]
+    fn excludes_experience_events() {
+        let events = HashMap::from([(
+            AvailablePlugins::timeline_plugin_experience,
+            (0..5).map(|v| event(v, Value::Null).event).collect(),
+        )]);
+        assert!(cluster_events(events, Duration::hours(6), None, 1).is_empty());
+    }
+
+    #[tokio::test]
+    async fn suggests_from_timeline() {
+        let media = [0, 1, 2, 20, 21, 40]
+            .map(|hour| event(hour, json!({ "path": format!("{}.jpg", hour) })).event);
+        let response = Ok::<_, APIError>(HashMap::from([
+            (
+                AvailablePlugins::timeline_plugin_experience,
+                vec![event(0, Value::Null).event, event(1, Value::Null).event],
+            ),
+            (AvailablePlugins::timeline_plugin_media_scan, media.to_vec()),
+        ]));
+        let (url, received) = serve_once(serde_json::to_string(&response).unwrap()).await;
+        let config: Config = toml::from_str(&format!(
+            "password = \"\"\nport = 0\nexperiences_folder = \"e\"\ncovers_folder = \"c\"\n\
+             timeline_url = \"{}\"\ntimeline_password = \"secret\"",
+            url
+        ))
+        .unwrap();
+        let range = TimeRange {
+            start: Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2024, 5, 3, 0, 0, 0).unwrap(),
+        };
+        let request = SuggestionsRequest {
+            range: range.clone(),
+            gap_hours: None,
+            max_distance_km: None,
+            min_events: Some(2),
+        };
+
+        let suggestions = suggest(&TimelineClient::new(&config), &request).await.unwrap();
+        let hour = |v: i64| range.start + Duration::hours(v);
+        assert_eq!(
+            suggestions.iter().map(|v| v.time.clone()).collect::<Vec<_>>(),
+            [
+                timing_from_bounds(hour(0), hour(2)),
+                timing_from_bounds(hour(20), hour(21)),
+            ]
+        );
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|v| {
+                    v.events
+                        .iter()
+                        .map(|v| {
+                            assert_eq!(v.plugin, AvailablePlugins::timeline_plugin_media_scan);
+                            v.event.data["path"].as_str().unwrap()
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+            [vec!["0.jpg", "1.jpg", "2.jpg"], vec!["20.jpg", "21.jpg"]]
+        );
+
+        let received = received.await.unwrap();
+        assert_eq!(received.path, "/api/events");
+        assert_eq!(received.headers.get("cookie").map(String::as_str), Some("pwd=secret"));
+        assert_eq!(serde_json::from_str::<TimeRange>(&received.body).unwrap(), range);
+    }
+}
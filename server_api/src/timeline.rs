@@ -0,0 +1,67 @@
+use {
+    crate::config::Config,
+    reqwest::{header::COOKIE, Client},
+    shared::types::{ExperienceError, ExperienceResult},
+    std::collections::HashMap,
+    timeline_types::{
+        api::{APIResult, CompressedEvent},
+        available_plugins::AvailablePlugins,
+        timing::TimeRange,
+    },
+    url::Url,
+};
+
+#[derive(Clone)]
+pub struct TimelineClient {
+    client: Client,
+    url: Url,
+    password: Option<String>,
+}
+
+impl TimelineClient {
+    pub fn new(config: &Config) -> TimelineClient {
+        TimelineClient {
+            client: Client::new(),
+            url: config.timeline_url.clone(),
+            password: config.timeline_password.clone(),
+        }
+    }
+
+    pub async fn get_events(
+        &self,
+        range: &TimeRange,
+    ) -> ExperienceResult<HashMap<AvailablePlugins, Vec<CompressedEvent>>> {
+        let url = self
+            .url
+            .join("api/events")
+            .map_err(|e| ExperienceError::TimelineError(e.to_string()))?;
+        let mut request = self.client.post(url).body(serde_json::to_string(range)?);
+        if let Some(password) = &self.password {
+            request = request.header(COOKIE, format!("pwd={}", password));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ExperienceError::TimelineError(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| ExperienceError::TimelineError(e.to_string()))?;
+
+        serde_json::from_str::<APIResult<HashMap<AvailablePlugins, Vec<CompressedEvent>>>>(
+            &response,
+        )?
+        .map_err(|e| ExperienceError::TimelineError(e.to_string()))
+    }
+
+    pub async fn get_plugins(&self, range: &TimeRange) -> ExperienceResult<Vec<AvailablePlugins>> {
+        Ok(self
+            .get_events(range)
+            .await?
+            .into_iter()
+            .filter(|(plugin, events)| {
+                *plugin != AvailablePlugins::timeline_plugin_experience && !events.is_empty()
+            })
+            .map(|(plugin, _)| plugin)
+            .collect())
+    }
+}
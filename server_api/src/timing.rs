@@ -0,0 +1,32 @@
+use {
+    chrono::{DateTime, Utc},
+    timeline_types::timing::{TimeRange, Timing},
+};
+
+pub fn timing_bounds(timing: &Timing) -> (DateTime<Utc>, DateTime<Utc>) {
+    match timing {
+        Timing::Instant(v) => (*v, *v),
+        Timing::Range(v) => (v.start, v.end),
+    }
+}
+
+pub fn timing_from_bounds(start: DateTime<Utc>, end: DateTime<Utc>) -> Timing {
+    if start == end {
+        Timing::Instant(start)
+    } else {
+        Timing::Range(TimeRange { start, end })
+    }
+}
+
+pub fn format_timing(timing: &Timing) -> String {
+    let (start, end) = timing_bounds(timing);
+    if start == end {
+        start.format("%Y-%m-%d %H:%M").to_string()
+    } else {
+        format!(
+            "{} - {}",
+            start.format("%Y-%m-%d %H:%M"),
+            end.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
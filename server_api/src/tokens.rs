@@ -0,0 +1,150 @@
+use {
+    crate::config::Config,
+    chrono::{Duration, Utc},
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    shared::types::{ApiTokenInfo, CreatedApiToken, ExperienceError, ExperienceResult, TokenScope},
+    std::{collections::HashMap, io::ErrorKind, path::PathBuf},
+    tokio::{
+        fs::{read_to_string, rename, write},
+        sync::RwLock,
+    },
+};
+
+const TOKEN_PREFIX: &str = "exp_";
+const LAST_USED_RESOLUTION: Duration = Duration::minutes(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredToken {
+    user: String,
+    hash: String,
+    #[serde(flatten)]
+    info: ApiTokenInfo,
+}
+
+pub struct TokenManager {
+    tokens_file: PathBuf,
+    tokens: RwLock<HashMap<String, StoredToken>>,
+}
+
+impl TokenManager {
+    pub async fn load(config: &Config) -> ExperienceResult<TokenManager> {
+        let tokens = match read_to_string(&config.tokens_file).await {
+            Ok(v) => serde_json::from_str::<Vec<StoredToken>>(&v)?
+                .into_iter()
+                .map(|v| (v.hash.clone(), v))
+                .collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+        };
+
+        Ok(TokenManager {
+            tokens_file: config.tokens_file.clone(),
+            tokens: RwLock::new(tokens),
+        })
+    }
+
+    pub async fn authenticate(&self, token: &str) -> Option<(String, TokenScope)> {
+        let hash = hash_token(token);
+        let now = Utc::now();
+        {
+            let tokens = self.tokens.read().await;
+            let stored = tokens.get(&hash)?;
+            if stored
+                .info
+                .last_used
+                .is_some_and(|v| now - v < LAST_USED_RESOLUTION)
+            {
+                return Some((stored.user.clone(), stored.info.scope));
+            }
+        }
+
+        let mut tokens = self.tokens.write().await;
+        let stored = tokens.get_mut(&hash)?;
+        stored.info.last_used = Some(now);
+        let res = (stored.user.clone(), stored.info.scope);
+        if let Err(e) = self.save(&tokens).await {
+            eprintln!("Unable to save API token usage: {}", e);
+        }
+        Some(res)
+    }
+
+    pub async fn get_tokens(&self, user: &str) -> Vec<ApiTokenInfo> {
+        let mut tokens = self
+            .tokens
+            .read()
+            .await
+            .values()
+            .filter(|v| v.user == user)
+            .map(|v| v.info.clone())
+            .collect::<Vec<_>>();
+        tokens.sort_by_key(|v| v.created);
+        tokens
+    }
+
+    pub async fn create_token(
+        &self,
+        user: &str,
+        name: String,
+        scope: TokenScope,
+    ) -> ExperienceResult<CreatedApiToken> {
+        let token = format!(
+            "{}{}{}",
+            TOKEN_PREFIX,
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let info = ApiTokenInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            scope,
+            created: Utc::now(),
+            last_used: None,
+        };
+
+        let hash = hash_token(&token);
+        let mut tokens = self.tokens.write().await;
+        tokens.insert(
+            hash.clone(),
+            StoredToken {
+                user: user.to_string(),
+                hash,
+                info: info.clone(),
+            },
+        );
+        self.save(&tokens).await?;
+        Ok(CreatedApiToken { info, token })
+    }
+
+    pub async fn revoke_token(&self, user: &str, id: &str) -> ExperienceResult<()> {
+        let mut tokens = self.tokens.write().await;
+        let hash = match tokens.values().find(|v| v.user == user && v.info.id == id) {
+            Some(v) => v.hash.clone(),
+            None => return Err(ExperienceError::NotFound(id.to_string())),
+        };
+        tokens.remove(&hash);
+        self.save(&tokens).await
+    }
+
+    pub async fn revoke_user_tokens(&self, user: &str) -> ExperienceResult<()> {
+        let mut tokens = self.tokens.write().await;
+        tokens.retain(|_, v| v.user != user);
+        self.save(&tokens).await
+    }
+
+    async fn save(&self, tokens: &HashMap<String, StoredToken>) -> ExperienceResult<()> {
+        let mut tokens = tokens.values().collect::<Vec<_>>();
+        tokens.sort_by_key(|v| v.info.created);
+        let temp = self.tokens_file.with_extension("json.tmp");
+        if let Err(e) = write(&temp, serde_json::to_string_pretty(&tokens)?).await {
+            return Err(ExperienceError::UnableToWrite(e.to_string()));
+        }
+        rename(&temp, &self.tokens_file)
+            .await
+            .map_err(|e| ExperienceError::UnableToWrite(e.to_string()))
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
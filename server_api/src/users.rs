@@ -0,0 +1,219 @@
+use {
+    crate::config::Config,
+    argon2::{
+        password_hash::{
+            rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+        },
+        Argon2,
+    },
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    shared::types::{ExperienceError, ExperienceResult, UserInfo},
+    std::{collections::HashMap, io::ErrorKind, path::PathBuf},
+    tokio::{
+        fs::{read_to_string, rename, write},
+        sync::RwLock,
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub name: String,
+    pub password: String,
+    #[serde(default)]
+    pub admin: bool,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub session_generation: u64,
+}
+
+impl User {
+    pub fn info(&self) -> UserInfo {
+        UserInfo {
+            name: self.name.clone(),
+            admin: self.admin,
+            groups: self.groups.clone(),
+        }
+    }
+
+    pub fn session_key(&self) -> String {
+        let password = hex::encode(Sha256::digest(self.password.as_bytes()));
+        format!("{}.{}", self.session_generation, &password[..16])
+    }
+}
+
+pub struct UserManager {
+    users_file: PathBuf,
+    users: RwLock<HashMap<String, User>>,
+    bootstrap: RwLock<User>,
+}
+
+impl UserManager {
+    pub async fn load(config: &Config) -> ExperienceResult<UserManager> {
+        let mut users = match read_to_string(&config.users_file).await {
+            Ok(v) => serde_json::from_str::<Vec<User>>(&v)?
+                .into_iter()
+                .map(|v| (v.name.clone(), v))
+                .collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(ExperienceError::FileError(e.to_string())),
+        };
+        let bootstrap_generation = users
+            .remove(&config.admin_name)
+            .map(|v| v.session_generation)
+            .unwrap_or(0);
+
+        let mut migrated = false;
+        for user in users.values_mut() {
+            if !is_password_hash(&user.password) {
+                user.password = hash_password(&user.password)?;
+                migrated = true;
+            }
+        }
+
+        let mut bootstrap_password = config.password.clone();
+        if !is_password_hash(&bootstrap_password) {
+            eprintln!(
+                "The configured password is not an argon2 hash, replace it with the output of \
+                 `hash-password <password>`"
+            );
+            bootstrap_password = hash_password(&bootstrap_password)?;
+        }
+
+        let manager = UserManager {
+            users_file: config.users_file.clone(),
+            users: RwLock::new(users),
+            bootstrap: RwLock::new(User {
+                name: config.admin_name.clone(),
+                password: bootstrap_password,
+                admin: true,
+                groups: Vec::new(),
+                session_generation: bootstrap_generation,
+            }),
+        };
+        if migrated {
+            manager
+                .save(&*manager.users.read().await, &*manager.bootstrap.read().await)
+                .await?;
+        }
+        Ok(manager)
+    }
+
+    pub async fn authenticate(&self, name: &str, password: &str) -> Option<User> {
+        self.get_user(name)
+            .await
+            .filter(|user| verify_password(password, &user.password))
+    }
+
+    pub async fn get_user(&self, name: &str) -> Option<User> {
+        let bootstrap = self.bootstrap.read().await;
+        if name == bootstrap.name {
+            Some(bootstrap.clone())
+        } else {
+            self.users.read().await.get(name).cloned()
+        }
+    }
+
+    pub async fn get_users(&self) -> Vec<UserInfo> {
+        let mut users = vec![self.bootstrap.read().await.info()];
+        users.extend(self.users.read().await.values().map(|v| v.info()));
+        users.sort_by(|a, b| a.name.cmp(&b.name));
+        users
+    }
+
+    pub async fn create_user(
+        &self,
+        name: &str,
+        password: &str,
+        admin: bool,
+        groups: Vec<String>,
+    ) -> ExperienceResult<()> {
+        if name.is_empty() || name == self.bootstrap.read().await.name {
+            return Err(ExperienceError::OperationNowAllowed(format!(
+                "Invalid user name: {}",
+                name
+            )));
+        }
+
+        let password = hash_password(password)?;
+        let mut users = self.users.write().await;
+        if users.contains_key(name) {
+            return Err(ExperienceError::OperationNowAllowed(format!(
+                "User already exists: {}",
+                name
+            )));
+        }
+        users.insert(
+            name.to_string(),
+            User {
+                name: name.to_string(),
+                password,
+                admin,
+                groups,
+                session_generation: 0,
+            },
+        );
+        self.save(&users, &*self.bootstrap.read().await).await
+    }
+
+    pub async fn delete_user(&self, name: &str) -> ExperienceResult<()> {
+        let mut users = self.users.write().await;
+        if users.remove(name).is_none() {
+            return Err(ExperienceError::NotFound(name.to_string()));
+        }
+        self.save(&users, &*self.bootstrap.read().await).await
+    }
+
+    pub async fn revoke_sessions(&self, name: &str) -> ExperienceResult<()> {
+        let mut users = self.users.write().await;
+        let mut bootstrap = self.bootstrap.write().await;
+        match users.get_mut(name) {
+            Some(user) => user.session_generation += 1,
+            None if name == bootstrap.name => bootstrap.session_generation += 1,
+            None => return Err(ExperienceError::NotFound(name.to_string())),
+        }
+        self.save(&users, &bootstrap).await
+    }
+
+    async fn save(&self, users: &HashMap<String, User>, bootstrap: &User) -> ExperienceResult<()> {
+        let bootstrap_sessions = User {
+            name: bootstrap.name.clone(),
+            password: String::new(),
+            admin: true,
+            groups: Vec::new(),
+            session_generation: bootstrap.session_generation,
+        };
+        let mut users = users.values().collect::<Vec<_>>();
+        if bootstrap.session_generation > 0 {
+            users.push(&bootstrap_sessions);
+        }
+        users.sort_by(|a, b| a.name.cmp(&b.name));
+        let temp = self.users_file.with_extension("json.tmp");
+        if let Err(e) = write(&temp, serde_json::to_string_pretty(&users)?).await {
+            return Err(ExperienceError::UnableToWrite(e.to_string()));
+        }
+        rename(&temp, &self.users_file)
+            .await
+            .map_err(|e| ExperienceError::UnableToWrite(e.to_string()))
+    }
+}
+
+pub fn hash_password(password: &str) -> ExperienceResult<String> {
+    Argon2::default()
+        .hash_password(password.as_bytes(), &SaltString::generate(&mut OsRng))
+        .map(|v| v.to_string())
+        .map_err(|e| ExperienceError::OperationNowAllowed(e.to_string()))
+}
+
+fn is_password_hash(password: &str) -> bool {
+    PasswordHash::new(password).is_ok()
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
@@ -0,0 +1,256 @@
+use {
+    crate::config::Webhook,
+    chrono::{DateTime, Utc},
+    hmac::{Hmac, Mac},
+    reqwest::{header::CONTENT_TYPE, Client},
+    serde::Serialize,
+    sha2::Sha256,
+    shared::types::ExperienceChange,
+    std::{path::PathBuf, sync::Arc, time::Duration},
+    tokio::{
+        fs::OpenOptions,
+        io::AsyncWriteExt,
+        sync::{broadcast, Mutex},
+        time::sleep,
+    },
+};
+
+const MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const SIGNATURE_HEADER: &str = "X-Experiences-Signature";
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    id: &'a str,
+    timestamp: DateTime<Utc>,
+    change: &'a ExperienceChange,
+}
+
+#[derive(Serialize)]
+struct DeliveryLogEntry<'a> {
+    id: &'a str,
+    url: &'a str,
+    attempt: u32,
+    timestamp: DateTime<Utc>,
+    status: Option<u16>,
+    error: Option<String>,
+}
+
+pub async fn dispatch(
+    mut changes: broadcast::Receiver<ExperienceChange>,
+    webhooks: Vec<Webhook>,
+    log: PathBuf,
+) {
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let client = Client::new();
+    let log = Arc::new(Mutex::new(log));
+    loop {
+        let change = match changes.recv().await {
+            Ok(v) => v,
+            Err(broadcast::error::RecvError::Lagged(v)) => {
+                eprintln!("Webhooks missed {} experience changes", v);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if matches!(
+            change,
+            ExperienceChange::CoverRegenerated { .. }
+                | ExperienceChange::NavigatorPositionChanged { .. }
+        ) {
+            continue;
+        }
+        for webhook in webhooks.iter() {
+            tokio::spawn(deliver(
+                client.clone(),
+                webhook.clone(),
+                change.clone(),
+                log.clone(),
+            ));
+        }
+    }
+}
+
+async fn deliver(
+    client: Client,
+    webhook: Webhook,
+    change: ExperienceChange,
+    log: Arc<Mutex<PathBuf>>,
+) {
+    let id = uuid::Uuid::new_v4().to_string();
+    let body = match serde_json::to_string(&WebhookPayload {
+        id: &id,
+        timestamp: Utc::now(),
+        change: &change,
+    }) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Unable to serialize webhook payload: {}", e);
+            return;
+        }
+    };
+
+    let mut backoff = INITIAL_BACKOFF;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(webhook.url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, &body)));
+        }
+
+        let (status, error) = match request.send().await {
+            Ok(v) if v.status().is_success() => (Some(v.status().as_u16()), None),
+            Ok(v) => (
+                Some(v.status().as_u16()),
+                Some(format!("Unexpected status: {}", v.status())),
+            ),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let delivered = error.is_none();
+
+        write_log(
+            &log,
+            &DeliveryLogEntry {
+                id: &id,
+                url: webhook.url.as_str(),
+                attempt,
+                timestamp: Utc::now(),
+                status,
+                error,
+            },
+        )
+        .await;
+
+        if delivered {
+            return;
+        }
+        if attempt < MAX_ATTEMPTS {
+            sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    eprintln!(
+        "Unable to deliver webhook {} to {} after {} attempts",
+        id, webhook.url, MAX_ATTEMPTS
+    );
+}
+
+fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+async fn write_log(log: &Mutex<PathBuf>, entry: &DeliveryLogEntry<'_>) {
+    let path = log.lock().await;
+    let mut line = match serde_json::to_string(entry) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Unable to serialize webhook delivery: {}", e);
+            return;
+        }
+    };
+    line.push('\n');
+
+    let res = match OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&*path)
+        .await
+    {
+        Ok(mut file) => file.write_all(line.as_bytes()).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = res {
+        eprintln!("Unable to write webhook delivery log: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::mock_server::serve_once, serde_json::Value};
+
+    #[tokio::test]
+    async fn delivers_signed_payload() {
+        let (url, received) = serve_once(String::new()).await;
+        let log = std::env::temp_dir().join(format!("webhooks-{}.log", uuid::Uuid::new_v4()));
+        let webhook = Webhook {
+            url: url.join("hook").unwrap(),
+            secret: Some("secret".to_string()),
+        };
+        let change = ExperienceChange::Edited {
+            experience_id: "experience".to_string(),
+        };
+
+        deliver(
+            Client::new(),
+            webhook,
+            change.clone(),
+            Arc::new(Mutex::new(log.clone())),
+        )
+        .await;
+
+        let received = received.await.unwrap();
+        assert_eq!(received.path, "/hook");
+        assert_eq!(
+            received.headers.get("content-type").map(String::as_str),
+            Some("application/json")
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(received.body.as_bytes());
+        let signature = received.headers.get(&SIGNATURE_HEADER.to_lowercase()).unwrap();
+        let signature = hex::decode(signature.strip_prefix("sha256=").unwrap()).unwrap();
+        mac.verify_slice(&signature).unwrap();
+
+        let payload: Value = serde_json::from_str(&received.body).unwrap();
+        assert!(payload["id"].is_string());
+        assert!(payload["timestamp"].is_string());
+        assert_eq!(payload["change"], serde_json::to_value(&change).unwrap());
+
+        let delivery: Value =
+            serde_json::from_str(tokio::fs::read_to_string(&log).await.unwrap().trim()).unwrap();
+        assert_eq!(delivery["id"], payload["id"]);
+        assert_eq!(delivery["attempt"], 1);
+        assert_eq!(delivery["status"], 200);
+        tokio::fs::remove_file(log).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dispatches_deletions_and_skips_live_only_changes() {
+        let (url, received) = serve_once(String::new()).await;
+        let log = std::env::temp_dir().join(format!("webhooks-{}.log", uuid::Uuid::new_v4()));
+        let (changes, receiver) = broadcast::channel(8);
+        let dispatcher = tokio::spawn(dispatch(
+            receiver,
+            vec![Webhook { url, secret: None }],
+            log.clone(),
+        ));
+
+        let deleted = ExperienceChange::Deleted {
+            experience_id: "experience".to_string(),
+        };
+        changes
+            .send(ExperienceChange::CoverRegenerated {
+                experience_id: "experience".to_string(),
+            })
+            .unwrap();
+        changes.send(deleted.clone()).unwrap();
+
+        let received = received.await.unwrap();
+        let payload: Value = serde_json::from_str(&received.body).unwrap();
+        assert_eq!(payload["change"], serde_json::to_value(&deleted).unwrap());
+        assert!(!received.headers.contains_key(&SIGNATURE_HEADER.to_lowercase()));
+
+        drop(changes);
+        dispatcher.await.unwrap();
+        let _ = tokio::fs::remove_file(log).await;
+    }
+}
@@ -5,11 +5,14 @@ use serde::{Deserialize, Serialize};
 use timeline_types::{
     api::{CompressedEvent, EventWrapper},
     available_plugins::AvailablePlugins,
+    timing::Timing,
 };
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct ExperienceEvent {
     pub favorite: bool,
+    #[serde(default)]
+    pub private: bool,
     pub id: String,
     pub event: CompressedEvent,
 }
@@ -35,11 +38,33 @@ pub struct FavoriteRequest {
     pub favorite: bool,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct EventPrivacyRequest {
+    pub event_id: String,
+    pub private: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Experience {
     pub events: HashMap<AvailablePlugins, Vec<ExperienceEvent>>,
     pub public: bool,
     pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub children: Vec<String>,
+    #[serde(default)]
+    pub connection_details: HashMap<String, ConnectionDetails>,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub acl: Vec<AccessEntry>,
+    #[serde(default)]
+    pub created_time: Option<Timing>,
 }
 
 #[cfg_attr(feature = "server", derive(Serialize))]
@@ -51,6 +76,7 @@ pub enum ExperienceError {
     ParsingError(String),
     UnableToWrite(String),
     OperationNowAllowed(String),
+    TimelineError(String),
 }
 
 impl fmt::Display for ExperienceError {
@@ -65,6 +91,9 @@ impl fmt::Display for ExperienceError {
             ExperienceError::OperationNowAllowed(v) => {
                 write!(f, "The performed operation is not allowed: {}", v)
             }
+            ExperienceError::TimelineError(v) => {
+                write!(f, "Unable to communicate with the timeline: {}", v)
+            }
         }
     }
 }
@@ -83,3 +112,35 @@ impl From<serde_json::Error> for ExperienceError {
 pub use experiences_types_lib::types::CreateExperienceRequest;
 
 pub use experiences_types_lib::types::CompressedExperienceEvent;
+
+pub use experiences_types_lib::types::{ExperienceDetails, ExperienceTemplate, SaveTemplateRequest};
+
+pub use experiences_types_lib::types::{ConnectionDetails, ConnectionRelationship};
+
+pub use experiences_types_lib::types::{ExperienceSubgraph, GraphEdge, GraphNode};
+
+pub use experiences_types_lib::types::ExperienceStats;
+
+pub use experiences_types_lib::types::{EventMemory, ExperienceMemory, Memories};
+
+pub use experiences_types_lib::types::{
+    AcceptSuggestionRequest, ExperienceSuggestion, SuggestedEvent, SuggestionsRequest,
+};
+
+pub use experiences_types_lib::types::PullEventsRequest;
+
+pub use experiences_types_lib::types::{EventRefresh, RefreshReport, RefreshRequest};
+
+pub use experiences_types_lib::types::ExperienceChange;
+
+pub use experiences_types_lib::types::{CreateUserRequest, LoginRequest, UserInfo};
+
+pub use experiences_types_lib::types::{CreateShareRequest, ShareToken};
+
+pub use experiences_types_lib::types::{AccessEntry, AccessRole, AccessSubject};
+
+pub use experiences_types_lib::types::{
+    ApiTokenInfo, CreateApiTokenRequest, CreatedApiToken, TokenScope,
+};
+
+pub use experiences_types_lib::types::{AuditAction, AuditEntry, AuditQuery};